@@ -4,15 +4,34 @@
 use md5::Context as md5_Context;
 use open::that as open_that;
 use rfd::FileDialog;
-use slint::SharedString;
+use slint::{Model, ModelRc, SharedString, Timer, TimerMode, VecModel};
+use std::cell::RefCell;
+use std::collections::HashMap;
 use std::error::Error;
 use std::fs::File;
 use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::time::{Duration, SystemTime};
 
+use crate::seed_generation::batch_statistics::{get_batch_statistics, write_batch_statistics};
+use crate::seed_generation::game_world::GameWorld;
+use crate::seed_generation::http_server::SeedServer;
+use crate::seed_generation::item_randomization::player_inventory::PlayerInventory;
+use crate::seed_generation::memory_card::{read_memory_card, SaveProgress};
+use crate::seed_generation::ram_snapshot::read_ram_snapshot;
+use crate::seed_generation::output_paths::{CollisionHandling, OutputSettings};
+use crate::seed_generation::randomization_datastructures::{LevelID, RequiredItem};
+use crate::seed_generation::rom_patching::base_patch::BasePatch;
 use crate::seed_generation::rom_patching::bsdiff_patching::apply_patchfile;
+use crate::seed_generation::sealed_spoilerlog::unlock_spoilerlog;
 use crate::seed_generation::seed_gen_main::generate_seed;
+use crate::seed_generation::spoilerlog::{get_seed_hash, get_seed_hash_icons};
+use crate::seed_generation::version::get_generator_version;
+use crate::seed_generation::write_rando_db::{get_db_seed, read_db_from_rom};
+use crate::seed_generation::tracker::{get_spoiler_free_entries, get_tracker_entries, TRACKED_ITEMS};
 use crate::seed_generation::seed_settings::{
-    BossGarageRequirements, FinalOxideUnlock, GeneralSettings, QualityOfLifeSettings, RandomizationSettings, RelicTime, RewardShuffle, SeedSettings, TrickSettings, WarppadShuffle, WarppadUnlockRequirements
+    BossGarageRequirements, BossGarageShuffle, ExcludedLocations, FinalOxideUnlock, GemCupTracks, GeneralSettings, Goal, HintSettings, HintSheetSettings, ItemPlando, LogicLevel, QualityOfLifeSettings, RandomizationSettings, RelicTime, RelicTimeOverrides, RelicTimeSettings, RelicTimeTargets, RewardShuffle, SeedSettings, StartingInventory, TrickSettings, WarppadShuffle, WarppadUnlockRequirements
 };
 
 slint::include_modules!();
@@ -26,8 +45,54 @@ enum RomValidState {
     Valid = 3,
 }
 
+/// Everything the tracker window shows is rebuilt from this
+#[derive(Default)]
+struct TrackerState {
+    /// Counts of the tracked items, in the order of TRACKED_ITEMS
+    item_counts: Vec<u8>,
+    beaten_tracks: Vec<LevelID>,
+    oxide_races_won: u8,
+    /// Requirements typed in by warp pad location, for spoiler-free tracking
+    entered_requirements: HashMap<LevelID, (String, String)>,
+    /// World and settings of the seed generated last
+    seed: Option<(GameWorld, SeedSettings)>,
+    seed_hash: String,
+    /// RAM snapshot file being auto-tracked, and when it was last read
+    ram_snapshot: Option<(PathBuf, Option<SystemTime>)>,
+    spoiler_free: bool,
+    /// Track of every warp pad row currently shown
+    row_tracks: Vec<Option<LevelID>>,
+}
+
 fn main() -> Result<(), Box<dyn Error>> {
+    // Batch mode runs without any UI:
+    // ctrrandomizer-standalone --batch <number of seeds> <settings file> <output .json/.csv>
+    let args: Vec<String> = std::env::args().collect();
+    if args.get(1).is_some_and(|x| x == "--batch") {
+        return run_batch_mode(&args[2..]);
+    }
+    // ctrrandomizer-standalone --progress <memory card>
+    if args.get(1).is_some_and(|x| x == "--progress") {
+        return run_progress_mode(&args[2..]);
+    }
+    // ctrrandomizer-standalone --unlock-spoiler <sealed spoiler log> <passphrase>
+    if args.get(1).is_some_and(|x| x == "--unlock-spoiler") {
+        return run_unlock_spoiler_mode(&args[2..]);
+    }
+    // ctrrandomizer-standalone --serve <port> <vanilla rom> [spoiler token]
+    if args.get(1).is_some_and(|x| x == "--serve") {
+        return run_serve_mode(&args[2..]);
+    }
+
     let ui = MainWindow::new()?;
+    ui.set_version(SharedString::from(get_generator_version()));
+    let tracker = TrackerWindow::new()?;
+    let tracker_state = Rc::new(RefCell::new(TrackerState {
+        item_counts: vec![0; TRACKED_ITEMS.len()],
+        spoiler_free: true,
+        ..TrackerState::default()
+    }));
+    refresh_tracker(&tracker, &mut tracker_state.borrow_mut());
 
     let main_ui_weak = ui.as_weak();
     let main_window = main_ui_weak.unwrap();
@@ -44,21 +109,47 @@ fn main() -> Result<(), Box<dyn Error>> {
         let _ = open_that("https://github.com/icebound777/CTR-Randomizer-Standalone/blob/master/docs/CTRRandoHandbook.md");
     });
 
+    let tracker_weak = tracker.as_weak();
+    let state = tracker_state.clone();
     ui.on_gen_seed(move || {
         // Check if patch file is present: if it is, just patch ROM, otherwise
         // run seed generation
         if !main_window.get_patchfile_path().is_empty() {
+            let base_patch = match get_base_patch(&main_window) {
+                Ok(x) => x,
+                Err(msg) => {
+                    main_window.invoke_show_error_popup(SharedString::from(msg));
+                    return;
+                }
+            };
             let patch_result = apply_patchfile(
                 main_window.get_rom_path().as_str(),
                 main_window.get_patchfile_path().as_str(),
+                &base_patch.manifest,
+                &get_output_settings(&main_window),
             );
-            if patch_result.is_err() {
-                panic!()
-            }
-            main_window.invoke_show_gen_success_popup(
-                SharedString::from(patch_result.unwrap().file_name().unwrap().to_string_lossy().to_string()),
-                SharedString::from(""),
-            );
+            match patch_result {
+                Ok((new_rom, warning)) => {
+                    // Patch files don't name their seed, so read it from the
+                    // patched ROM for showing the seed hash icons
+                    let seed = read_db_from_rom(&new_rom, &base_patch.manifest)
+                        .ok()
+                        .and_then(|x| get_db_seed(&x));
+                    let seed_hash_icons = get_seed_hash_icons_model(seed);
+                    main_window.invoke_show_gen_success_popup(
+                        SharedString::from(new_rom.file_name().unwrap().to_string_lossy().to_string()),
+                        SharedString::from(""),
+                        SharedString::from(""),
+                        SharedString::from(warning.unwrap_or_default()),
+                        seed_hash_icons.clone(),
+                    );
+                    main_window.set_verify_rom_path(SharedString::from(new_rom.to_string_lossy().as_ref()));
+                    main_window.set_seed_hash_icons(seed_hash_icons);
+                },
+                Err(msg) => {
+                    main_window.invoke_show_error_popup(SharedString::from(msg.to_string()));
+                },
+            };
         } else {
             // Collect settings chosen via UI
             let chosen_rando_settings = RandomizationSettings {
@@ -86,6 +177,11 @@ fn main() -> Result<(), Box<dyn Error>> {
                 .unwrap(),
                 bossgarage_unlock_requirements: BossGarageRequirements::try_from(main_window.get_bossgarage_unlock_requirements()).unwrap(),
                 autounlock_ctrchallenge_relicrace: main_window.get_autounlock_ctrchallenge_relicrace(),
+                gentle_difficulty_curve: main_window.get_gentle_difficulty_curve(),
+                shuffle_unlockables: main_window.get_shuffle_unlockables(),
+                gem_cup_tracks: GemCupTracks::try_from(main_window.get_gem_cup_tracks()).unwrap(),
+                boss_garage_shuffle: BossGarageShuffle::try_from(main_window.get_boss_garage_shuffle()).unwrap(),
+                shuffle_hub_entrances: main_window.get_shuffle_hub_entrances(),
             };
             let chosen_qol_settings = QualityOfLifeSettings {
                 skip_mask_hints: main_window.get_qol_skip_mask_hints(),
@@ -95,6 +191,43 @@ fn main() -> Result<(), Box<dyn Error>> {
             let chosen_trick_settings = TrickSettings {
                 helper_tiziano: main_window.get_trick_helper_tiziano(),
                 helper_ta: main_window.get_trick_helper_ta(),
+                logic_level: LogicLevel::try_from(main_window.get_trick_logic_level()).unwrap(),
+            };
+            let starting_inventory = match StartingInventory::try_from(
+                main_window.get_starting_inventory().as_str(),
+            ) {
+                Ok(x) => x,
+                Err(msg) => {
+                    main_window.invoke_show_error_popup(SharedString::from(msg));
+                    return;
+                }
+            };
+            let excluded_locations = match ExcludedLocations::try_from(
+                main_window.get_excluded_locations().as_str(),
+            ) {
+                Ok(x) => x,
+                Err(msg) => {
+                    main_window.invoke_show_error_popup(SharedString::from(msg));
+                    return;
+                }
+            };
+            let item_plando = match ItemPlando::try_from(
+                main_window.get_item_plando().as_str(),
+            ) {
+                Ok(x) => x,
+                Err(msg) => {
+                    main_window.invoke_show_error_popup(SharedString::from(msg));
+                    return;
+                }
+            };
+            let relic_time_overrides = match RelicTimeOverrides::try_from(
+                main_window.get_relic_time_overrides().as_str(),
+            ) {
+                Ok(x) => x,
+                Err(msg) => {
+                    main_window.invoke_show_error_popup(SharedString::from(msg));
+                    return;
+                }
             };
             let chosen_general_settings = GeneralSettings {
                 rr_required_minimum_time: RelicTime::try_from(
@@ -102,9 +235,41 @@ fn main() -> Result<(), Box<dyn Error>> {
                 )
                 .unwrap(),
                 rr_require_perfects: main_window.get_rr_require_perfects(),
-                oxide_final_challenge_unlock: FinalOxideUnlock::try_from(
+                oxide_final_challenge_unlock: FinalOxideUnlock::try_from((
                     main_window.get_oxide_final_challenge_unlock(),
-                )
+                    main_window.get_oxide_final_challenge_count(),
+                ))
+                .unwrap(),
+                goal: Goal::try_from((
+                    main_window.get_goal(),
+                    main_window.get_goal_count(),
+                ))
+                .unwrap(),
+                starting_inventory,
+                excluded_locations,
+                item_plando,
+                relic_times: RelicTimeSettings {
+                    targets: RelicTimeTargets::try_from((
+                        main_window.get_relic_time_targets(),
+                        main_window.get_relic_time_percent(),
+                        main_window.get_relic_time_max_percent(),
+                    ))
+                    .unwrap(),
+                    overrides: relic_time_overrides,
+                },
+                hints: HintSettings::try_from((
+                    main_window.get_hint_item_area(),
+                    main_window.get_hint_not_required(),
+                    main_window.get_hint_race_type(),
+                ))
+                .unwrap(),
+                hint_sheet: HintSheetSettings::try_from((
+                    main_window.get_hint_sheet_way_of_the_hero(),
+                    main_window.get_hint_sheet_barren(),
+                    main_window.get_hint_sheet_item_location(),
+                    main_window.get_hint_sheet_starting_hints(),
+                    main_window.get_hint_sheet_reveal_interval(),
+                ))
                 .unwrap(),
             };
             let chosen_settings = SeedSettings {
@@ -114,19 +279,46 @@ fn main() -> Result<(), Box<dyn Error>> {
                 tricks: chosen_trick_settings,
                 write_spoilerlog: main_window.get_write_spoilerlog(),
                 write_patchfile: main_window.get_write_patchfile(),
+                race_mode: main_window.get_race_mode(),
             };
 
             // Generate seed
             let rom_path = main_window.get_rom_path();
             let rom_path = rom_path.as_str();
-            let gen_result = generate_seed(rom_path, &chosen_settings);
+            let base_patch = match get_base_patch(&main_window) {
+                Ok(x) => x,
+                Err(msg) => {
+                    main_window.invoke_show_error_popup(SharedString::from(msg));
+                    return;
+                }
+            };
+            let gen_result = generate_seed(
+                rom_path,
+                &chosen_settings,
+                main_window.get_race_passphrase().as_str(),
+                &base_patch,
+                &get_output_settings(&main_window),
+            );
 
             match gen_result {
                 Ok(seed_meta) => {
+                    let seed_hash_icons = get_seed_hash_icons_model(Some(seed_meta.seed));
                     main_window.invoke_show_gen_success_popup(
                         SharedString::from(seed_meta.seed_filename),
-                        SharedString::from(seed_meta.seed_hash),
+                        SharedString::from(seed_meta.seed_hash.as_str()),
+                        SharedString::from(seed_meta.seed_estimate),
+                        SharedString::from(""),
+                        seed_hash_icons.clone(),
                     );
+                    main_window.set_verify_rom_path(SharedString::from(seed_meta.new_rom_path.to_string_lossy().as_ref()));
+                    main_window.set_seed_hash_icons(seed_hash_icons);
+
+                    let mut state = state.borrow_mut();
+                    // Race seeds are only tracked spoiler-free
+                    state.seed = seed_meta.game_world.map(|x| (x, chosen_settings));
+                    state.seed_hash = seed_meta.seed_hash;
+                    state.spoiler_free = state.seed.is_none();
+                    refresh_tracker(&tracker_weak.unwrap(), &mut state);
                 },
                 Err(msg) => {
                     main_window.invoke_show_error_popup(SharedString::from(msg));
@@ -192,7 +384,367 @@ fn main() -> Result<(), Box<dyn Error>> {
         main_window.set_patchfile_path(SharedString::from(""));
     });
 
+    let main_ui_weak = ui.as_weak();
+    let main_window = main_ui_weak.unwrap();
+    ui.on_pick_base_patch(move || {
+        // Open File Picker Dialog
+        let files = FileDialog::new()
+            .add_filter("Base Patch or ROM", &["bsdiff4", "bin"])
+            .pick_file();
+        if let Some(pathbuf) = files {
+            // Check the manifest right away instead of on generating
+            match BasePatch::from_file(&pathbuf) {
+                Ok(_) => main_window.set_base_patch_path(SharedString::from(pathbuf.to_string_lossy().as_ref())),
+                Err(msg) => main_window.invoke_show_error_popup(SharedString::from(msg)),
+            }
+        }
+    });
+
+    let main_ui_weak = ui.as_weak();
+    let main_window = main_ui_weak.unwrap();
+    ui.on_clear_base_patch_path(move || {
+        main_window.set_base_patch_path(SharedString::from(""));
+    });
+
+    let main_ui_weak = ui.as_weak();
+    let main_window = main_ui_weak.unwrap();
+    ui.on_pick_verify_rom(move || {
+        // Open File Picker Dialog
+        let files = FileDialog::new().add_filter(".bin", &["bin"]).pick_file();
+        if let Some(pathbuf) = files {
+            let seed = get_base_patch(&main_window)
+                .and_then(|x| read_db_from_rom(&pathbuf, &x.manifest))
+                .and_then(|x| get_db_seed(&x).ok_or("The ROM does not show a seed hash!".to_owned()));
+            match seed {
+                Ok(seed) => {
+                    main_window.set_verify_rom_path(SharedString::from(pathbuf.to_string_lossy().as_ref()));
+                    main_window.set_seed_hash_icons(get_seed_hash_icons_model(Some(seed)));
+                },
+                Err(msg) => main_window.invoke_show_error_popup(SharedString::from(msg)),
+            }
+        }
+    });
+
+    let main_ui_weak = ui.as_weak();
+    let main_window = main_ui_weak.unwrap();
+    ui.on_pick_output_directory(move || {
+        // Open Folder Picker Dialog
+        if let Some(pathbuf) = FileDialog::new().pick_folder() {
+            main_window.set_output_directory(SharedString::from(pathbuf.to_string_lossy().as_ref()));
+        }
+    });
+
+    let main_ui_weak = ui.as_weak();
+    let main_window = main_ui_weak.unwrap();
+    ui.on_clear_output_directory(move || {
+        main_window.set_output_directory(SharedString::from(""));
+    });
+
+    let tracker_weak = tracker.as_weak();
+    ui.on_open_tracker(move || {
+        let _ = tracker_weak.unwrap().show();
+    });
+
+    let tracker_weak = tracker.as_weak();
+    let state = tracker_state.clone();
+    tracker.on_item_changed(move |index, count| {
+        let mut state = state.borrow_mut();
+        state.item_counts[index as usize] = u8::try_from(count).unwrap_or_default();
+        refresh_tracker(&tracker_weak.unwrap(), &mut state);
+    });
+
+    let tracker_weak = tracker.as_weak();
+    let state = tracker_state.clone();
+    tracker.on_beaten_toggled(move |index, beaten| {
+        let mut state = state.borrow_mut();
+        if let Some(track) = state.row_tracks[index as usize] {
+            state.beaten_tracks.retain(|x| *x != track);
+            if beaten {
+                state.beaten_tracks.push(track);
+            }
+        }
+        refresh_tracker(&tracker_weak.unwrap(), &mut state);
+    });
+
+    let tracker_weak = tracker.as_weak();
+    let state = tracker_state.clone();
+    tracker.on_requirement_edited(move |index, stage, text| {
+        let mut state = state.borrow_mut();
+        if let Some(location) = state.row_tracks[index as usize] {
+            let requirements = state.entered_requirements.entry(location).or_default();
+            if stage == 1 {
+                requirements.0 = text.to_string();
+            } else {
+                requirements.1 = text.to_string();
+            }
+        }
+        refresh_tracker(&tracker_weak.unwrap(), &mut state);
+    });
+
+    let tracker_weak = tracker.as_weak();
+    let state = tracker_state.clone();
+    tracker.on_spoiler_free_toggled(move |spoiler_free| {
+        let mut state = state.borrow_mut();
+        state.spoiler_free = spoiler_free;
+        refresh_tracker(&tracker_weak.unwrap(), &mut state);
+    });
+
+    let tracker_weak = tracker.as_weak();
+    let main_window = main_ui_weak.unwrap();
+    let state = tracker_state.clone();
+    tracker.on_load_memory_card(move || {
+        let files = FileDialog::new()
+            .add_filter("Memory Card", &["mcr", "mcd", "srm", "gme", "mem", "vgs", "vmp", "mcs"])
+            .pick_file();
+        if let Some(pathbuf) = files {
+            match read_memory_card(&pathbuf, Vec::new()) {
+                Ok(progress) => {
+                    let mut state = state.borrow_mut();
+                    let items = progress.inventory.get_items();
+                    state.item_counts = TRACKED_ITEMS.iter().map(|x| items[x]).collect();
+                    state.beaten_tracks = progress.inventory.get_beaten_tracks().to_vec();
+                    state.oxide_races_won = progress.oxide_races_won;
+                    refresh_tracker(&tracker_weak.unwrap(), &mut state);
+                },
+                Err(msg) => {
+                    main_window.invoke_show_error_popup(SharedString::from(msg));
+                },
+            }
+        }
+    });
+
+    let tracker_weak = tracker.as_weak();
+    let state = tracker_state.clone();
+    tracker.on_toggle_auto_tracking(move || {
+        let tracker = tracker_weak.unwrap();
+        let mut state = state.borrow_mut();
+        if state.ram_snapshot.take().is_none() {
+            let files = FileDialog::new().pick_file();
+            if let Some(pathbuf) = files {
+                state.ram_snapshot = Some((pathbuf, None));
+            }
+        }
+        tracker.set_auto_tracking(state.ram_snapshot.is_some());
+        tracker.set_status(SharedString::from(""));
+    });
+
+    // Re-read the auto-tracked RAM snapshot whenever the emulator writes it
+    let auto_tracking_timer = Timer::default();
+    let tracker_weak = tracker.as_weak();
+    let state = tracker_state.clone();
+    auto_tracking_timer.start(TimerMode::Repeated, Duration::from_secs(1), move || {
+        let tracker = tracker_weak.unwrap();
+        let mut state = state.borrow_mut();
+        let Some((path, last_read)) = state.ram_snapshot.clone() else {
+            return;
+        };
+        let modified = std::fs::metadata(&path).and_then(|x| x.modified()).ok();
+        if modified.is_some() && modified == last_read {
+            return;
+        }
+        state.ram_snapshot = Some((path.clone(), modified));
+
+        let tricks = state.seed.as_ref().map(|(_, settings)| settings.tricks.get_enabled_tricks()).unwrap_or_default();
+        match read_ram_snapshot(&path, tricks) {
+            Ok(snapshot) => {
+                let items = snapshot.progress.inventory.get_items();
+                state.item_counts = TRACKED_ITEMS.iter().map(|x| items[x]).collect();
+                state.beaten_tracks = snapshot.progress.inventory.get_beaten_tracks().to_vec();
+                state.oxide_races_won = snapshot.progress.oxide_races_won;
+
+                let running_seed_hash = snapshot.get_seed().map(get_seed_hash).unwrap_or_default();
+                tracker.set_status(SharedString::from(
+                    if state.seed.is_some() && running_seed_hash != state.seed_hash {
+                        "The running game is not the seed generated last!".to_string()
+                    } else if running_seed_hash.is_empty() {
+                        "Auto-tracking".to_string()
+                    } else {
+                        format!("Auto-tracking: {running_seed_hash}")
+                    }
+                ));
+                refresh_tracker(&tracker, &mut state);
+            },
+            Err(msg) => {
+                tracker.set_status(SharedString::from(msg.replace('\n', " ")));
+            },
+        }
+    });
+
     let _ = ui.run();
 
     Ok(())
 }
+
+/// Generate statistics for many seeds of one settings string, like the one
+/// shown in the error popup of a failed seed
+fn run_batch_mode(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let [num_seeds, settings_path, output_path] = args else {
+        return Err("Usage: --batch <number of seeds> <settings file> <output .json/.csv>".into());
+    };
+    let num_seeds: u32 = num_seeds
+        .parse()
+        .map_err(|_| format!("Invalid number of seeds '{num_seeds}'!"))?;
+    let chosen_settings = SeedSettings::try_from(std::fs::read_to_string(settings_path)?.as_str())?;
+    chosen_settings.validate()?;
+
+    let statistics = get_batch_statistics(&chosen_settings, num_seeds);
+    write_batch_statistics(Path::new(output_path), &statistics)?;
+    println!(
+        "{} of {} seeds failed ({:.1}%)",
+        statistics.failed_seeds.len(),
+        num_seeds,
+        statistics.get_failure_rate() * 100.0,
+    );
+
+    Ok(())
+}
+
+/// Unseal the spoiler log of a race mode seed after the race
+fn run_unlock_spoiler_mode(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let [sealed_path, passphrase] = args else {
+        return Err("Usage: --unlock-spoiler <sealed spoiler log> <passphrase>".into());
+    };
+    let (spoilerlog_path, warning) = unlock_spoilerlog(Path::new(sealed_path), passphrase)?;
+    if let Some(warning) = warning {
+        println!("Warning: {warning}");
+    }
+    println!("Spoiler log written to {}", spoilerlog_path.display());
+
+    Ok(())
+}
+
+/// Generate seeds for other programs over a local HTTP API
+fn run_serve_mode(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let (port, rom_path, spoiler_token) = match args {
+        [port, rom_path] => (port, rom_path, None),
+        [port, rom_path, spoiler_token] => (port, rom_path, Some(spoiler_token.clone())),
+        _ => return Err("Usage: --serve <port> <vanilla rom> [spoiler token]".into()),
+    };
+    let port: u16 = port.parse().map_err(|_| format!("Invalid port '{port}'!"))?;
+    if !Path::new(rom_path).is_file() {
+        return Err(format!("Could not find the vanilla ROM '{rom_path}'!").into());
+    }
+
+    SeedServer::new(rom_path, spoiler_token).run(port)?;
+
+    Ok(())
+}
+
+/// Print the progress of the randomizer save on a memory card image
+fn run_progress_mode(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let [memory_card_path] = args else {
+        return Err("Usage: --progress <memory card>".into());
+    };
+    let progress = read_memory_card(Path::new(memory_card_path), Vec::new())?;
+
+    println!("Completion: {}%", progress.get_completion_percentage());
+    let mut items: Vec<_> = progress.inventory.get_items().into_iter().filter(|(_, count)| *count > 0).collect();
+    items.sort();
+    for (item, count) in items {
+        println!("{item} (x{count})");
+    }
+    let mut tracks = progress.inventory.get_beaten_tracks().to_vec();
+    tracks.sort();
+    println!(
+        "Beaten tracks: {}",
+        tracks.iter().map(std::string::ToString::to_string).collect::<Vec<_>>().join(", "),
+    );
+
+    Ok(())
+}
+
+/// Rebuild the tracker window from the tracker state, checking every warp pad
+/// and boss garage against the tracked items
+fn refresh_tracker(tracker: &TrackerWindow, state: &mut TrackerState) {
+    let mut inventory = PlayerInventory::with_tricks(
+        state.seed.as_ref().map(|(_, settings)| settings.tricks.get_enabled_tricks()).unwrap_or_default(),
+    );
+    for (item, count) in TRACKED_ITEMS.iter().zip(&state.item_counts) {
+        for _ in 0..*count {
+            inventory.add_item(*item);
+        }
+    }
+    for track in &state.beaten_tracks {
+        inventory.add_track(*track);
+    }
+
+    let entries = match &state.seed {
+        Some((game_world, settings)) if !state.spoiler_free => get_tracker_entries(&inventory, game_world, settings),
+        _ => get_spoiler_free_entries(&inventory, &state.entered_requirements),
+    };
+    state.row_tracks = entries.iter().map(|x| x.track).collect();
+
+    let items: Vec<TrackerItem> = TRACKED_ITEMS
+        .iter()
+        .zip(&state.item_counts)
+        .map(|(item, count)| TrackerItem {
+            name: SharedString::from(item.to_string()),
+            count: i32::from(*count),
+            maximum: i32::from(RequiredItem::try_from(*item).map_or(0, |x| x.get_max_count())),
+        })
+        .collect();
+    let warppads: Vec<TrackerWarppad> = entries
+        .into_iter()
+        .map(|x| TrackerWarppad {
+            name: SharedString::from(x.name),
+            requirement_1: SharedString::from(x.requirement_1),
+            has_stage_2: x.requirement_2.is_some(),
+            requirement_2: SharedString::from(x.requirement_2.unwrap_or_default()),
+            unlocked_1: x.unlocked_1,
+            unlocked_2: x.unlocked_2,
+            can_be_beaten: x.track.is_some(),
+            beaten: x.track.is_some_and(|track| state.beaten_tracks.contains(&track)),
+        })
+        .collect();
+
+    // Update the rows in place where possible, so text fields keep their focus
+    set_model_rows(tracker.get_items(), items, |x| tracker.set_items(x));
+    set_model_rows(tracker.get_warppads(), warppads, |x| tracker.set_warppads(x));
+
+    tracker.set_has_seed(state.seed.is_some());
+    tracker.set_spoiler_free(state.spoiler_free || state.seed.is_none());
+    tracker.set_completion(SharedString::from(format!(
+        "{}%",
+        SaveProgress { inventory, oxide_races_won: state.oxide_races_won }.get_completion_percentage(),
+    )));
+}
+
+/// The built-in base patch, or the one chosen in the Base Mod tab
+fn get_base_patch(main_window: &MainWindow) -> Result<BasePatch, String> {
+    let base_patch_path = main_window.get_base_patch_path();
+    if base_patch_path.is_empty() {
+        Ok(BasePatch::builtin())
+    } else {
+        BasePatch::from_file(Path::new(base_patch_path.as_str()))
+    }
+}
+
+/// Where the files of a seed go, as chosen in the Output tab
+fn get_output_settings(main_window: &MainWindow) -> OutputSettings {
+    let output_directory = main_window.get_output_directory();
+    OutputSettings {
+        directory: (!output_directory.is_empty()).then(|| PathBuf::from(output_directory.as_str())),
+        filename_template: main_window.get_filename_template().to_string(),
+        preset_name: main_window.get_preset_name().to_string(),
+        collision_handling: CollisionHandling::try_from(main_window.get_collision_handling()).unwrap(),
+        seed_subfolder: main_window.get_seed_subfolder(),
+    }
+}
+
+/// Seed hash icons for the UI, no icons if the seed is unknown
+fn get_seed_hash_icons_model(seed: Option<u32>) -> ModelRc<i32> {
+    let icons: Vec<i32> = seed
+        .map(|x| get_seed_hash_icons(x).map(|x| x as i32).to_vec())
+        .unwrap_or_default();
+    ModelRc::new(VecModel::from(icons))
+}
+
+fn set_model_rows<T: Clone + 'static>(model: ModelRc<T>, rows: Vec<T>, set_model: impl Fn(ModelRc<T>)) {
+    if model.row_count() == rows.len() {
+        for (index, row) in rows.into_iter().enumerate() {
+            model.set_row_data(index, row);
+        }
+    } else {
+        set_model(ModelRc::new(VecModel::from(rows)));
+    }
+}
@@ -0,0 +1,199 @@
+use std::{collections::BTreeMap, io, path::Path};
+
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use serde_json::{json, to_string_pretty};
+
+use crate::seed_generation::{
+    item_randomization::randomize_items::{get_final_item_placement, get_spheres},
+    randomization_datastructures::{ItemLocation, RaceReward},
+    randomize_game::get_randomized_game,
+    seed_estimate::get_seed_estimate,
+    seed_settings::SeedSettings,
+};
+
+/// Aggregated results of generating many seeds with the same settings.
+/// Histograms map a value to the number of seeds that had it.
+pub struct BatchStatistics {
+    pub settings: String,
+    pub num_seeds: u32,
+    pub failed_seeds: Vec<u32>,
+    pub placement_attempts: BTreeMap<u32, u32>,
+    pub playthrough_spheres: BTreeMap<usize, u32>,
+    pub required_races: BTreeMap<usize, u32>,
+    pub estimated_minutes: BTreeMap<u32, u32>,
+    /// How often each item landed on each location
+    pub item_locations: BTreeMap<ItemLocation, BTreeMap<RaceReward, u32>>,
+    /// How often each item type is required by a warp pad, "Free" for warp
+    /// pads without a requirement
+    pub warppad_requirements: BTreeMap<String, u32>,
+}
+
+/// Generate `num_seeds` random seeds with the given settings, without
+/// touching any ROM, and collect statistics about them.
+pub fn get_batch_statistics(chosen_settings: &SeedSettings, num_seeds: u32) -> BatchStatistics {
+    let mut statistics = BatchStatistics {
+        settings: chosen_settings.to_string(),
+        num_seeds,
+        failed_seeds: Vec::new(),
+        placement_attempts: BTreeMap::new(),
+        playthrough_spheres: BTreeMap::new(),
+        required_races: BTreeMap::new(),
+        estimated_minutes: BTreeMap::new(),
+        item_locations: BTreeMap::new(),
+        warppad_requirements: BTreeMap::new(),
+    };
+
+    for _ in 0..num_seeds {
+        let mut seed: u32;
+        loop {
+            seed = rand::random::<u32>();
+
+            if seed != 0u32 {
+                break;
+            }
+        }
+
+        let rng = ChaCha8Rng::seed_from_u64(u64::from(seed));
+        let Ok(game_setup) = get_randomized_game(rng, seed, chosen_settings) else {
+            statistics.failed_seeds.push(seed);
+            continue;
+        };
+
+        *statistics.placement_attempts.entry(game_setup.placement_attempts).or_default() += 1;
+
+        let item_placement = get_final_item_placement(&game_setup.game_world, chosen_settings);
+        let num_spheres = get_spheres(&item_placement, chosen_settings).len();
+        *statistics.playthrough_spheres.entry(num_spheres).or_default() += 1;
+
+        let seed_estimate = get_seed_estimate(&game_setup, chosen_settings);
+        *statistics.required_races.entry(seed_estimate.required_locations.len()).or_default() += 1;
+        // Bucket into steps of 15 minutes to keep the histogram readable
+        let estimated_minutes = seed_estimate.duration_seconds / 900 * 15;
+        *statistics.estimated_minutes.entry(estimated_minutes).or_default() += 1;
+
+        for (location, item) in game_setup.game_world.get_race_rewards() {
+            *statistics.item_locations.entry(location).or_default().entry(item).or_default() += 1;
+        }
+
+        for requirement in game_setup.game_world.get_warppad_unlocks().values().flatten() {
+            let key = if requirement.count == 0 {
+                String::from("Free")
+            } else {
+                requirement.item_type.to_string()
+            };
+            *statistics.warppad_requirements.entry(key).or_default() += 1;
+        }
+    }
+
+    statistics
+}
+
+impl BatchStatistics {
+    pub fn get_failure_rate(&self) -> f64 {
+        if self.num_seeds == 0 {
+            return 0.0;
+        }
+        self.failed_seeds.len() as f64 / f64::from(self.num_seeds)
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        fn get_histogram<T: ToString>(histogram: &BTreeMap<T, u32>) -> serde_json::Map<String, serde_json::Value> {
+            histogram
+                .iter()
+                .map(|(k, v)| (k.to_string(), json!(v)))
+                .collect()
+        }
+
+        json!({
+            "settings": self.settings,
+            "seeds": self.num_seeds,
+            "failures": self.failed_seeds.len(),
+            "failure_rate": self.get_failure_rate(),
+            "failed_seeds": self.failed_seeds,
+            "placement_attempts": get_histogram(&self.placement_attempts),
+            "playthrough_spheres": get_histogram(&self.playthrough_spheres),
+            "required_races": get_histogram(&self.required_races),
+            "estimated_minutes": get_histogram(&self.estimated_minutes),
+            "item_locations": self.item_locations
+                .iter()
+                .map(|(location, items)| (
+                    format!("{} {}", location.levelid, location.racetype),
+                    json!(get_histogram(items)),
+                ))
+                .collect::<serde_json::Map<String, serde_json::Value>>(),
+            "warppad_requirements": get_histogram(&self.warppad_requirements),
+        })
+    }
+
+    /// One row per value, as "statistic,key,item,count"
+    pub fn to_csv(&self) -> String {
+        let mut rows = vec![String::from("statistic,key,item,count")];
+
+        rows.push(format!("seeds,,,{}", self.num_seeds));
+        rows.push(format!("failures,,,{}", self.failed_seeds.len()));
+        for seed in &self.failed_seeds {
+            rows.push(format!("failed_seed,{seed},,1"));
+        }
+        for (name, histogram) in [
+            ("placement_attempts", self.placement_attempts.iter().map(|(k, v)| (k.to_string(), *v)).collect::<Vec<_>>()),
+            ("playthrough_spheres", self.playthrough_spheres.iter().map(|(k, v)| (k.to_string(), *v)).collect()),
+            ("required_races", self.required_races.iter().map(|(k, v)| (k.to_string(), *v)).collect()),
+            ("estimated_minutes", self.estimated_minutes.iter().map(|(k, v)| (k.to_string(), *v)).collect()),
+            ("warppad_requirement", self.warppad_requirements.iter().map(|(k, v)| (k.clone(), *v)).collect()),
+        ] {
+            for (key, count) in histogram {
+                rows.push(format!("{name},{key},,{count}"));
+            }
+        }
+        for (location, items) in &self.item_locations {
+            for (item, count) in items {
+                rows.push(format!("item_location,{} {},{},{}", location.levelid, location.racetype, item, count));
+            }
+        }
+
+        rows.join("\n") + "\n"
+    }
+}
+
+/// Write the statistics as CSV if the file name ends in ".csv", and as JSON
+/// otherwise
+pub fn write_batch_statistics(path: &Path, statistics: &BatchStatistics) -> Result<(), io::Error> {
+    let contents = if path.extension().is_some_and(|x| x.eq_ignore_ascii_case("csv")) {
+        statistics.to_csv()
+    } else {
+        to_string_pretty(&statistics.to_json()).unwrap()
+    };
+
+    std::fs::write(path, contents)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn batch_statistics() {
+        let settings = SeedSettings::try_from(
+            "rnd1;111;11;1;1;0;0;0;0;0;0;\ngen00018000;\ninv-;\nexc-;\nrlt0;-;\nhnt000000;\nhsh00000000;000;\npln-;\n\
+            qol00000;\ntrk002;\nwrt100;",
+        )
+        .unwrap();
+        settings.validate().unwrap();
+        let statistics = get_batch_statistics(&settings, 3);
+
+        assert!(statistics.failed_seeds.is_empty());
+        for histogram in [&statistics.required_races, &statistics.playthrough_spheres] {
+            assert_eq!(histogram.values().sum::<u32>(), 3);
+        }
+        assert_eq!(statistics.placement_attempts.values().sum::<u32>(), 3);
+        assert!(statistics.item_locations.values().all(|x| x.values().sum::<u32>() == 3));
+
+        let csv = statistics.to_csv();
+        assert!(csv.lines().any(|x| x == "seeds,,,3"));
+        assert!(csv.lines().any(|x| x == "failures,,,0"));
+        let json = statistics.to_json();
+        assert_eq!(json["seeds"], 3);
+        assert_eq!(json["settings"], settings.to_string());
+    }
+}
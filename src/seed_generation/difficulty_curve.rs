@@ -0,0 +1,64 @@
+use crate::seed_generation::{
+    game_world::GameWorld,
+    item_randomization::randomize_items::{get_final_item_placement, get_spheres},
+    randomization_datastructures::{ItemLocation, LevelID, RaceType},
+    seed_settings::SeedSettings,
+};
+
+/// Bot difficulty in adventure mode by number of trophies, as listed in the
+/// handbook. Higher values mean faster bots.
+pub const BOT_DIFFICULTY_BY_TROPHIES: [u16; 17] = [
+    68, 77, 86, 95, 103, 112, 121, 130, 138, 147, 156, 165, 173, 182, 191, 200, 208,
+];
+
+pub fn get_bot_difficulty(num_trophies: u8) -> u16 {
+    BOT_DIFFICULTY_BY_TROPHIES[usize::from(num_trophies).min(BOT_DIFFICULTY_BY_TROPHIES.len() - 1)]
+}
+
+/// Estimated bot difficulty of a race at the point it becomes reachable
+#[derive(Debug, Clone, Copy)]
+pub struct RaceDifficulty {
+    pub location: ItemLocation,
+    pub sphere: usize,
+    pub num_trophies: u8,
+    pub bot_difficulty: u16,
+}
+
+/// Whether this race has bots scaling with the trophy count. Boss races have
+/// a single opponent, and neither relic races nor battle arenas have bots.
+fn has_trophy_scaled_bots(location: &ItemLocation) -> bool {
+    match location.racetype {
+        RaceType::TrophyRace | RaceType::GemCup => true,
+        RaceType::CtrOrCrystalChallenge => ![
+            LevelID::SkullRock,
+            LevelID::RampageRuins,
+            LevelID::RockyRoad,
+            LevelID::NitroCourt,
+        ]
+        .contains(&location.levelid),
+        _ => false,
+    }
+}
+
+/// Walk the playthrough of the final game world, and estimate the bot
+/// difficulty of every race with bots when it first becomes reachable.
+pub fn get_difficulty_curve(game_world: &GameWorld, chosen_settings: &SeedSettings) -> Vec<RaceDifficulty> {
+    let item_placement = get_final_item_placement(game_world, chosen_settings);
+
+    get_spheres(&item_placement, chosen_settings)
+        .into_iter()
+        .enumerate()
+        .flat_map(|(sphere_index, sphere)| {
+            sphere
+                .locations
+                .into_iter()
+                .filter(has_trophy_scaled_bots)
+                .map(move |location| RaceDifficulty {
+                    location,
+                    sphere: sphere_index,
+                    num_trophies: sphere.num_trophies,
+                    bot_difficulty: get_bot_difficulty(sphere.num_trophies),
+                })
+        })
+        .collect()
+}
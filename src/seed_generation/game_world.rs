@@ -59,10 +59,10 @@ impl GameWorld {
             warp_pad: WarpPad
         ) {
             all_unlocks.insert((warp_pad.current_level_id, UnlockStage::One), warp_pad.unlock_1.requirement);
-            if warp_pad.unlock_2.is_some() {
+            if let Some(unlock_2) = warp_pad.unlock_2 {
                 all_unlocks.insert(
                     (warp_pad.current_level_id, UnlockStage::Two),
-                    warp_pad.unlock_2.expect("checked by if").requirement
+                    unlock_2.requirement
                 );
             }
         }
@@ -149,8 +149,8 @@ impl GameWorld {
             }
 
             add_single_reward(all_warppad_rewards, warp_pad.current_level_id,  warp_pad.unlock_1);
-            if warp_pad.unlock_2.is_some() {
-                add_single_reward(all_warppad_rewards, warp_pad.current_level_id,  warp_pad.unlock_2.expect("checked by if"));
+            if let Some(unlock_2) = warp_pad.unlock_2 {
+                add_single_reward(all_warppad_rewards, warp_pad.current_level_id,  unlock_2);
             }
         }
 
@@ -162,9 +162,6 @@ impl GameWorld {
         add_single_warppad_rewards(&mut race_rewards, self.hub_1.warppad_3);
         add_single_warppad_rewards(&mut race_rewards, self.hub_1.warppad_4);
         add_single_warppad_rewards(&mut race_rewards, self.hub_1.warppad_arena);
-        if let Rewards::BossRaceRewards(BossRaceRewards { single_reward: rew }) = self.hub_1.boss_garage.reward {
-            race_rewards.insert(ItemLocation{levelid: LevelID::RoosTubes, racetype: RaceType::BossRace}, rew);
-        }
 
         // Hub 2 - Lost Temple
         add_single_warppad_rewards(&mut race_rewards, self.hub_2.warppad_1);
@@ -172,9 +169,6 @@ impl GameWorld {
         add_single_warppad_rewards(&mut race_rewards, self.hub_2.warppad_3);
         add_single_warppad_rewards(&mut race_rewards, self.hub_2.warppad_4);
         add_single_warppad_rewards(&mut race_rewards, self.hub_2.warppad_arena);
-        if let Rewards::BossRaceRewards(BossRaceRewards { single_reward: rew }) = self.hub_2.boss_garage.reward {
-            race_rewards.insert(ItemLocation{levelid: LevelID::PapusPyramid, racetype: RaceType::BossRace}, rew);
-        }
 
         // Hub 3 - Glacial Park
         add_single_warppad_rewards(&mut race_rewards, self.hub_3.warppad_1);
@@ -182,9 +176,6 @@ impl GameWorld {
         add_single_warppad_rewards(&mut race_rewards, self.hub_3.warppad_3);
         add_single_warppad_rewards(&mut race_rewards, self.hub_3.warppad_4);
         add_single_warppad_rewards(&mut race_rewards, self.hub_3.warppad_arena);
-        if let Rewards::BossRaceRewards(BossRaceRewards { single_reward: rew }) = self.hub_3.boss_garage.reward {
-            race_rewards.insert(ItemLocation{levelid: LevelID::DragonMines, racetype: RaceType::BossRace}, rew);
-        }
 
         // Hub 4 - Citadel City
         add_single_warppad_rewards(&mut race_rewards, self.hub_4.warppad_1);
@@ -192,9 +183,6 @@ impl GameWorld {
         add_single_warppad_rewards(&mut race_rewards, self.hub_4.warppad_3);
         add_single_warppad_rewards(&mut race_rewards, self.hub_4.warppad_4);
         add_single_warppad_rewards(&mut race_rewards, self.hub_4.warppad_arena);
-        if let Rewards::BossRaceRewards(BossRaceRewards { single_reward: rew }) = self.hub_4.boss_garage.reward {
-            race_rewards.insert(ItemLocation{levelid: LevelID::HotAirSkyway, racetype: RaceType::BossRace}, rew);
-        }
 
         // Hub 5? - Gem Stone Valley
         add_single_warppad_rewards(&mut race_rewards, self.gemstone_valley.warppad_1);
@@ -205,6 +193,17 @@ impl GameWorld {
         add_single_warppad_rewards(&mut race_rewards, self.gemstone_valley.cup_warppad_4);
         add_single_warppad_rewards(&mut race_rewards, self.gemstone_valley.cup_warppad_5);
 
+        // Boss garages, the race belongs to the boss and not to the garage.
+        // Beating N. Oxide is not stored as a race reward.
+        for (_, boss_garage) in self.get_boss_garages() {
+            if boss_garage.boss == BossCharacter::NOxide {
+                continue;
+            }
+            if let Rewards::BossRaceRewards(BossRaceRewards { single_reward: rew }) = boss_garage.reward {
+                race_rewards.insert(ItemLocation{levelid: boss_garage.boss.get_levelid(), racetype: RaceType::BossRace}, rew);
+            }
+        }
+
         race_rewards
     }
 
@@ -382,49 +381,60 @@ impl GameWorld {
     }
 
     pub fn get_garage_unlocks(&self) -> HashMap<BossCharacter, UnlockRequirement> {
-        HashMap::from([
-            (
-                BossCharacter::RipperRoo,
-                self.hub_1.boss_garage.requirement.clone()
-            ),
-            (
-                BossCharacter::PapuPapu,
-                self.hub_2.boss_garage.requirement.clone()
-            ),
-            (
-                BossCharacter::KomodoJoe,
-                self.hub_3.boss_garage.requirement.clone()
-            ),
-            (
-                BossCharacter::Pinstripe,
-                self.hub_4.boss_garage.requirement.clone()
-            ),
-            (
-                BossCharacter::NOxide,
-                self.gemstone_valley.boss_garage.requirement.clone()
-            ),
-        ])
+        self.get_boss_garages()
+            .into_iter()
+            .map(|(_, boss_garage)| (boss_garage.boss, boss_garage.requirement.clone()))
+            .collect()
     }
 
     pub fn set_garage_unlocks(&mut self, garage_unlocks: HashMap<BossCharacter, UnlockRequirement>) {
+        let boss_hubs = self.get_boss_hubs();
         for (boss, req) in garage_unlocks {
-            match boss {
-                BossCharacter::RipperRoo => {
-                    self.hub_1.boss_garage.requirement = req;
-                },
-                BossCharacter::PapuPapu => {
-                    self.hub_2.boss_garage.requirement = req;
-                },
-                BossCharacter::KomodoJoe => {
-                    self.hub_3.boss_garage.requirement = req;
-                },
-                BossCharacter::Pinstripe => {
-                    self.hub_4.boss_garage.requirement = req;
-                },
-                BossCharacter::NOxide => {
-                    self.gemstone_valley.boss_garage.requirement = req;
-                },
-            };
+            self.get_boss_garage_mut(boss_hubs[&boss]).requirement = req;
+        }
+    }
+
+    fn get_boss_garages(&self) -> [(Hubs, &BossGarage); 5] {
+        [
+            (Hubs::NSanityBeach, &self.hub_1.boss_garage),
+            (Hubs::TheLostRuins, &self.hub_2.boss_garage),
+            (Hubs::GlacierPark, &self.hub_3.boss_garage),
+            (Hubs::CitadelCity, &self.hub_4.boss_garage),
+            (Hubs::GemStoneValley, &self.gemstone_valley.boss_garage),
+        ]
+    }
+
+    fn get_boss_garage_mut(&mut self, hub: Hubs) -> &mut BossGarage {
+        match hub {
+            Hubs::NSanityBeach => &mut self.hub_1.boss_garage,
+            Hubs::TheLostRuins => &mut self.hub_2.boss_garage,
+            Hubs::GlacierPark => &mut self.hub_3.boss_garage,
+            Hubs::CitadelCity => &mut self.hub_4.boss_garage,
+            Hubs::GemStoneValley => &mut self.gemstone_valley.boss_garage,
+        }
+    }
+
+    /// The hub whose garage every boss is behind
+    pub fn get_boss_hubs(&self) -> HashMap<BossCharacter, Hubs> {
+        self.get_boss_garages()
+            .into_iter()
+            .map(|(hub, boss_garage)| (boss_garage.boss, hub))
+            .collect()
+    }
+
+    /// Move bosses into other garages. Bosses take their reward with them,
+    /// the garage requirements stay with their garage.
+    pub fn set_boss_hubs(&mut self, boss_hubs: HashMap<BossCharacter, Hubs>) {
+        let boss_rewards: HashMap<BossCharacter, Rewards> = self
+            .get_boss_garages()
+            .into_iter()
+            .map(|(_, boss_garage)| (boss_garage.boss, boss_garage.reward))
+            .collect();
+
+        for (boss, hub) in boss_hubs {
+            let boss_garage = self.get_boss_garage_mut(hub);
+            boss_garage.boss = boss;
+            boss_garage.reward = boss_rewards[&boss];
         }
     }
 
@@ -438,6 +448,41 @@ impl GameWorld {
         ])
     }
 
+    /// The hub behind every hub door, with doors named after the hub they
+    /// lead to in the vanilla game
+    pub fn get_hub_entrances(&self) -> HashMap<Hubs, Hubs> {
+        HashMap::from([
+            (self.hub_1.entrance, Hubs::NSanityBeach),
+            (self.gemstone_valley.entrance, Hubs::GemStoneValley),
+            (self.hub_2.entrance, Hubs::TheLostRuins),
+            (self.hub_3.entrance, Hubs::GlacierPark),
+            (self.hub_4.entrance, Hubs::CitadelCity),
+        ])
+    }
+
+    /// Move hubs behind other doors. Hubs take their warppads and boss garage
+    /// with them, the key requirements stay with their door.
+    pub fn set_hub_entrances(&mut self, hub_entrances: HashMap<Hubs, Hubs>) {
+        let hub_requirements = self.get_hub_requirements();
+        let door_requirements: HashMap<Hubs, Option<UnlockRequirementItem>> = self
+            .get_hub_entrances()
+            .into_iter()
+            .map(|(door, hub)| (door, hub_requirements[&hub]))
+            .collect();
+
+        for (door, hub) in hub_entrances {
+            let (entrance, requirement) = match hub {
+                Hubs::NSanityBeach => (&mut self.hub_1.entrance, &mut self.hub_1.requirement),
+                Hubs::TheLostRuins => (&mut self.hub_2.entrance, &mut self.hub_2.requirement),
+                Hubs::GlacierPark => (&mut self.hub_3.entrance, &mut self.hub_3.requirement),
+                Hubs::CitadelCity => (&mut self.hub_4.entrance, &mut self.hub_4.requirement),
+                Hubs::GemStoneValley => (&mut self.gemstone_valley.entrance, &mut self.gemstone_valley.requirement),
+            };
+            *entrance = door;
+            *requirement = door_requirements[&door];
+        }
+    }
+
     pub fn set_rewards(&mut self, reward_placement: HashMap<ItemLocation, RaceReward>) {
         let current_warppad_links = self.get_warppad_links();
         let inverted_warppad_links: HashMap<LevelID, LevelID> = current_warppad_links.iter().map(|(k, v)| (*v, *k)).collect();
@@ -703,24 +748,8 @@ impl GameWorld {
                     }
                 }
                 RaceType::BossRace => {
-                    let boss_location = match location.levelid {
-                        LevelID::RoosTubes => {
-                            &mut self.hub_1.boss_garage
-                        },
-                        LevelID::PapusPyramid => {
-                            &mut self.hub_2.boss_garage
-                        },
-                        LevelID::DragonMines => {
-                            &mut self.hub_3.boss_garage
-                        },
-                        LevelID::HotAirSkyway => {
-                            &mut self.hub_4.boss_garage
-                        },
-                        LevelID::OxideStation => {
-                            &mut self.gemstone_valley.boss_garage
-                        },
-                        _ => panic!()
-                    };
+                    let boss = BossCharacter::from_levelid(location.levelid).expect("boss races are on boss tracks");
+                    let boss_location = self.get_boss_garage_mut(self.get_boss_hubs()[&boss]);
 
                     boss_location.reward = Rewards::BossRaceRewards(
                         BossRaceRewards {
@@ -733,7 +762,7 @@ impl GameWorld {
     }
 }
 
-#[derive(PartialEq, Eq, Hash, Clone)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, PartialOrd, Ord)]
 pub enum Hubs {
     NSanityBeach,
     GemStoneValley,
@@ -742,8 +771,21 @@ pub enum Hubs {
     CitadelCity,
 }
 
+impl std::fmt::Display for Hubs {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            Hubs::NSanityBeach => "N. Sanity Beach",
+            Hubs::GemStoneValley => "Gem Stone Valley",
+            Hubs::TheLostRuins => "The Lost Ruins",
+            Hubs::GlacierPark => "Glacier Park",
+            Hubs::CitadelCity => "Citadel City",
+        })
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct GenericHub {
+    entrance: Hubs,
     requirement: Option<UnlockRequirementItem>,
     pub warppad_1: WarpPad,
     pub warppad_2: WarpPad,
@@ -755,6 +797,7 @@ pub struct GenericHub {
 
 #[derive(Debug, Clone)]
 pub struct GemStoneValleyHub {
+    entrance: Hubs,
     requirement: Option<UnlockRequirementItem>,
     pub warppad_1: WarpPad,
     pub warppad_2: WarpPad,
@@ -959,15 +1002,52 @@ pub struct BossGarage {
     pub reward: Rewards,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub enum BossCharacter {
-    RipperRoo,
-    PapuPapu,
-    KomodoJoe,
-    Pinstripe,
-    NOxide,
+    RipperRoo = 0,
+    PapuPapu = 1,
+    KomodoJoe = 2,
+    Pinstripe = 3,
+    NOxide = 4,
 }
 
+impl BossCharacter {
+    /// The track of the boss race, which also names its item location
+    pub fn get_levelid(&self) -> LevelID {
+        match self {
+            BossCharacter::RipperRoo => LevelID::RoosTubes,
+            BossCharacter::PapuPapu => LevelID::PapusPyramid,
+            BossCharacter::KomodoJoe => LevelID::DragonMines,
+            BossCharacter::Pinstripe => LevelID::HotAirSkyway,
+            BossCharacter::NOxide => LevelID::OxideStation,
+        }
+    }
+
+    pub fn from_levelid(levelid: LevelID) -> Option<BossCharacter> {
+        BOSS_CHARACTERS.into_iter().find(|x| x.get_levelid() == levelid)
+    }
+}
+
+impl std::fmt::Display for BossCharacter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            BossCharacter::RipperRoo => "Ripper Roo",
+            BossCharacter::PapuPapu => "Papu Papu",
+            BossCharacter::KomodoJoe => "Komodo Joe",
+            BossCharacter::Pinstripe => "Pinstripe",
+            BossCharacter::NOxide => "N. Oxide",
+        })
+    }
+}
+
+pub const BOSS_CHARACTERS: [BossCharacter; 5] = [
+    BossCharacter::RipperRoo,
+    BossCharacter::PapuPapu,
+    BossCharacter::KomodoJoe,
+    BossCharacter::Pinstripe,
+    BossCharacter::NOxide,
+];
+
 #[derive(Debug, Clone, Copy)]
 pub struct RaceUnlock {
     pub requirement: Option<UnlockRequirementItem>,
@@ -1022,6 +1102,7 @@ pub struct GemCupRewards {
 pub fn get_vanilla_gameworld() -> GameWorld {
     GameWorld {
         hub_1: GenericHub {
+            entrance: Hubs::NSanityBeach,
             requirement: None,
             warppad_1: WarpPad::new(LevelID::CrashCove, LevelID::CrashCove),
             warppad_2: WarpPad::new(LevelID::RoosTubes, LevelID::RoosTubes),
@@ -1044,6 +1125,7 @@ pub fn get_vanilla_gameworld() -> GameWorld {
             warppad_arena: WarpPad::new(LevelID::SkullRock, LevelID::SkullRock),
         },
         hub_2: GenericHub {
+            entrance: Hubs::TheLostRuins,
             requirement: Some(UnlockRequirementItem { item_type: RequiredItem::Key, count: 1 }),
             warppad_1: WarpPad::new(LevelID::CocoPark, LevelID::CocoPark),
             warppad_2: WarpPad::new(LevelID::TigerTemple, LevelID::TigerTemple),
@@ -1064,6 +1146,7 @@ pub fn get_vanilla_gameworld() -> GameWorld {
             warppad_arena: WarpPad::new(LevelID::RampageRuins, LevelID::RampageRuins)
         },
         hub_3: GenericHub {
+            entrance: Hubs::GlacierPark,
             requirement: Some(UnlockRequirementItem { item_type: RequiredItem::Key, count: 2 }),
             warppad_1: WarpPad::new(LevelID::BlizzardBluff, LevelID::BlizzardBluff),
             warppad_2: WarpPad::new(LevelID::DragonMines, LevelID::DragonMines),
@@ -1084,6 +1167,7 @@ pub fn get_vanilla_gameworld() -> GameWorld {
             warppad_arena: WarpPad::new(LevelID::RockyRoad, LevelID::RockyRoad)
         },
         hub_4: GenericHub {
+            entrance: Hubs::CitadelCity,
             requirement: Some(UnlockRequirementItem { item_type: RequiredItem::Key, count: 3 }),
             warppad_1: WarpPad::new(LevelID::NGinLabs, LevelID::NGinLabs),
             warppad_2: WarpPad::new(LevelID::CortexCastle, LevelID::CortexCastle),
@@ -1104,6 +1188,7 @@ pub fn get_vanilla_gameworld() -> GameWorld {
             warppad_arena: WarpPad::new(LevelID::NitroCourt, LevelID::NitroCourt)
         },
         gemstone_valley: GemStoneValleyHub {
+            entrance: Hubs::GemStoneValley,
             requirement: Some(UnlockRequirementItem { item_type: RequiredItem::Key, count: 1 }),
             warppad_1: WarpPad::new(LevelID::TurboTrack, LevelID::TurboTrack),
             warppad_2: WarpPad::new(LevelID::SlideColiseum, LevelID::SlideColiseum),
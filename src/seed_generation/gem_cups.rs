@@ -0,0 +1,75 @@
+use std::collections::BTreeMap;
+
+use rand::seq::{IndexedRandom, SliceRandom};
+use rand_chacha::ChaCha8Rng;
+
+use crate::seed_generation::{
+    randomization_datastructures::LevelID,
+    seed_settings::GemCupTracks,
+};
+
+pub const GEM_CUPS: [LevelID; 5] = [
+    LevelID::CupRed,
+    LevelID::CupGreen,
+    LevelID::CupBlue,
+    LevelID::CupYellow,
+    LevelID::CupPurple,
+];
+
+/// The race tracks of every hub. Turbo Track and Slide Coliseum never show up
+/// in gem cups.
+pub const HUB_TRACKS: [[LevelID; 4]; 4] = [
+    [LevelID::CrashCove, LevelID::RoosTubes, LevelID::MysteryCaves, LevelID::SewerSpeedway],
+    [LevelID::TigerTemple, LevelID::CocoPark, LevelID::PapusPyramid, LevelID::DingoCanyon],
+    [LevelID::BlizzardBluff, LevelID::DragonMines, LevelID::PolarPass, LevelID::TinyArena],
+    [LevelID::NGinLabs, LevelID::CortexCastle, LevelID::HotAirSkyway, LevelID::OxideStation],
+];
+
+/// Pick the four tracks of every gem cup. Returns nothing for vanilla cups,
+/// the base mod keeps their line-ups as is then.
+pub fn get_gem_cup_tracks(seed: &mut ChaCha8Rng, gem_cup_tracks: GemCupTracks) -> BTreeMap<LevelID, [LevelID; 4]> {
+    let all_tracks: Vec<LevelID> = HUB_TRACKS.iter().flatten().copied().collect();
+
+    let line_ups: Vec<[LevelID; 4]> = match gem_cup_tracks {
+        GemCupTracks::Vanilla => return BTreeMap::new(),
+        GemCupTracks::Random => GEM_CUPS
+            .iter()
+            .map(|_| {
+                let tracks: Vec<LevelID> = all_tracks.choose_multiple(seed, 4).copied().collect();
+                tracks.try_into().expect("chose 4 tracks")
+            })
+            .collect(),
+        GemCupTracks::NoRepeats => {
+            // 20 spots for 16 tracks, so only the purple cup repeats tracks
+            // of the other cups
+            let mut tracks = all_tracks.clone();
+            tracks.shuffle(seed);
+            tracks.extend(all_tracks.choose_multiple(seed, 4).copied().collect::<Vec<_>>());
+
+            tracks
+                .chunks(4)
+                .map(|x| x.try_into().expect("chunks of 4 tracks"))
+                .collect()
+        }
+        GemCupTracks::ByHub => {
+            // Every colored cup gets the tracks of one hub, the purple cup
+            // gets one track of every hub
+            let mut hubs = HUB_TRACKS.to_vec();
+            hubs.shuffle(seed);
+            let mut line_ups: Vec<[LevelID; 4]> = hubs
+                .into_iter()
+                .map(|mut x| {
+                    x.shuffle(seed);
+                    x
+                })
+                .collect();
+            let mut purple_cup: [LevelID; 4] = HUB_TRACKS.map(|x| *x.choose(seed).expect("hubs have tracks"));
+            purple_cup.shuffle(seed);
+            line_ups.push(purple_cup);
+
+            line_ups
+        }
+    };
+
+    GEM_CUPS.into_iter().zip(line_ups).collect()
+}
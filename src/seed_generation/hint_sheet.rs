@@ -0,0 +1,205 @@
+use std::{
+    collections::{BTreeSet, HashSet},
+    io,
+    path::Path,
+};
+
+use rand::{seq::SliceRandom, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde_json::{json, to_string_pretty};
+
+use crate::seed_generation::{
+    item_randomization::{item_pool::get_preplaced_items, randomize_items::get_final_item_placement},
+    randomization_datastructures::{GameSetup, ItemLocation, LevelID, RaceReward},
+    seed_estimate::get_required_locations,
+    seed_settings::SeedSettings,
+    spoilerlog::get_seed_hash,
+};
+
+/// A hint for the printable hint sheet, independent of the hints in the ROM
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SheetHint {
+    /// A race holding an item needed to reach the goal
+    WayOfTheHero(ItemLocation),
+    /// A track where no race holds anything needed to reach the goal
+    Barren(LevelID),
+    ItemLocation {
+        location: ItemLocation,
+        item: RaceReward,
+    },
+}
+
+impl SheetHint {
+    pub fn get_kind(&self) -> &'static str {
+        match self {
+            SheetHint::WayOfTheHero(_) => "way_of_the_hero",
+            SheetHint::Barren(_) => "barren",
+            SheetHint::ItemLocation { .. } => "item_location",
+        }
+    }
+}
+
+impl std::fmt::Display for SheetHint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SheetHint::WayOfTheHero(x) => write!(f, "{} {} is on the way of the hero", x.levelid, x.racetype),
+            SheetHint::Barren(x) => write!(f, "Nothing at {x} is required"),
+            SheetHint::ItemLocation { location, item } => {
+                write!(f, "{} {} has a {}", location.levelid, location.racetype, item)
+            }
+        }
+    }
+}
+
+pub struct ScheduledHint {
+    /// Minutes after the start of the race the hint gets revealed at
+    pub reveal_minutes: u32,
+    pub hint: SheetHint,
+}
+
+/// Pick the hint sheet hints of a finished seed, in the order they get
+/// revealed. Nothing the player already knows gets hinted: unshuffled items,
+/// hand placed items, and excluded locations.
+pub fn get_hint_sheet(seed: u32, game_setup: &GameSetup, chosen_settings: &SeedSettings) -> Vec<ScheduledHint> {
+    let sheet_settings = chosen_settings.general.hint_sheet;
+    let Some(reward_shuffle) = chosen_settings.randomization.shuffle_race_rewards else {
+        return Vec::new();
+    };
+    if !chosen_settings.randomization.shuffle_adventure || sheet_settings.get_total() == 0 {
+        return Vec::new();
+    }
+
+    // Own stream, so the hint sheet doesn't change the rest of the seed
+    let mut rng = ChaCha8Rng::seed_from_u64(u64::from(seed));
+    rng.set_stream(1);
+
+    let item_placement = get_final_item_placement(&game_setup.game_world, chosen_settings);
+    let required_locations = get_required_locations(&game_setup.game_world, chosen_settings);
+    let known_locations: HashSet<ItemLocation> =
+        get_preplaced_items(&reward_shuffle, chosen_settings.force_vanilla_turbotrack())
+            .into_keys()
+            .chain(chosen_settings.general.item_plando.placements.keys().copied())
+            .collect();
+    let get_item = |location: &ItemLocation| {
+        item_placement[location]
+            .1
+            .filter(|x| !matches!(x, RaceReward::Nothing | RaceReward::BeatTheGame))
+    };
+    let mut hintable_locations: Vec<ItemLocation> = item_placement
+        .keys()
+        .filter(|x| !known_locations.contains(x) && !chosen_settings.is_excluded_location(x))
+        .copied()
+        .collect();
+    hintable_locations.sort();
+
+    let mut hints = Vec::new();
+    let mut hinted_locations = HashSet::new();
+
+    let mut way_of_the_hero: Vec<ItemLocation> = hintable_locations
+        .iter()
+        .filter(|x| required_locations.contains(x) && get_item(x).is_some())
+        .copied()
+        .collect();
+    way_of_the_hero.shuffle(&mut rng);
+    for location in way_of_the_hero.into_iter().take(usize::from(sheet_settings.way_of_the_hero_hints)) {
+        hinted_locations.insert(location);
+        hints.push(SheetHint::WayOfTheHero(location));
+    }
+
+    let tracks: BTreeSet<LevelID> = hintable_locations.iter().map(|x| x.levelid).collect();
+    let mut barren_tracks: Vec<LevelID> = tracks
+        .into_iter()
+        .filter(|levelid| !required_locations.iter().any(|x| x.levelid == *levelid))
+        .collect();
+    barren_tracks.shuffle(&mut rng);
+    for levelid in barren_tracks.into_iter().take(usize::from(sheet_settings.barren_hints)) {
+        hints.push(SheetHint::Barren(levelid));
+    }
+
+    let mut item_locations: Vec<ItemLocation> = hintable_locations
+        .iter()
+        .filter(|x| !hinted_locations.contains(x) && get_item(x).is_some())
+        .copied()
+        .collect();
+    item_locations.shuffle(&mut rng);
+    for location in item_locations.into_iter().take(usize::from(sheet_settings.item_location_hints)) {
+        let item = get_item(&location).expect("checked by filter");
+        hints.push(SheetHint::ItemLocation { location, item });
+    }
+
+    hints.shuffle(&mut rng);
+
+    let starting_hints = usize::from(sheet_settings.starting_hints);
+    let interval = u32::from(sheet_settings.reveal_interval_minutes);
+    hints
+        .into_iter()
+        .enumerate()
+        .map(|(index, hint)| ScheduledHint {
+            reveal_minutes: if index < starting_hints {
+                0
+            } else {
+                (index - starting_hints + 1) as u32 * interval
+            },
+            hint,
+        })
+        .collect()
+}
+
+fn format_reveal_time(minutes: u32) -> String {
+    format!("{}:{:02}", minutes / 60, minutes % 60)
+}
+
+fn get_markdown(seed: u32, hint_sheet: &[ScheduledHint]) -> String {
+    let mut lines = vec![
+        String::from("# Hint Sheet"),
+        String::new(),
+        format!("Seed hash: {}", get_seed_hash(seed)),
+    ];
+
+    let mut current_reveal = None;
+    for scheduled_hint in hint_sheet {
+        if current_reveal != Some(scheduled_hint.reveal_minutes) {
+            current_reveal = Some(scheduled_hint.reveal_minutes);
+            lines.push(String::new());
+            lines.push(if scheduled_hint.reveal_minutes == 0 {
+                String::from("## At the start")
+            } else {
+                format!("## After {}", format_reveal_time(scheduled_hint.reveal_minutes))
+            });
+            lines.push(String::new());
+        }
+        lines.push(format!("* {}", scheduled_hint.hint));
+    }
+
+    lines.join("\n") + "\n"
+}
+
+fn get_json(seed: u32, hint_sheet: &[ScheduledHint]) -> serde_json::Value {
+    json!({
+        "seed_hash": get_seed_hash(seed),
+        "hints": hint_sheet
+            .iter()
+            .map(|x| json!({
+                "reveal_minutes": x.reveal_minutes,
+                "reveal_time": format_reveal_time(x.reveal_minutes),
+                "kind": x.hint.get_kind(),
+                "hint": x.hint.to_string(),
+            }))
+            .collect::<Vec<_>>(),
+    })
+}
+
+/// Write the hint sheet next to the new ROM, once as Markdown for printing
+/// and once as JSON for tools like race bots
+pub fn write_hint_sheet(new_rom_path: &Path, seed: u32, hint_sheet: &[ScheduledHint]) -> Result<(), io::Error> {
+    let file_stem = new_rom_path.file_stem().unwrap().to_str().unwrap();
+
+    std::fs::write(
+        new_rom_path.with_file_name(format!("{file_stem}_hintsheet.md")),
+        get_markdown(seed, hint_sheet),
+    )?;
+    std::fs::write(
+        new_rom_path.with_file_name(format!("{file_stem}_hintsheet.json")),
+        to_string_pretty(&get_json(seed, hint_sheet)).unwrap(),
+    )
+}
@@ -0,0 +1,214 @@
+use std::collections::{HashMap, HashSet};
+
+use rand::seq::SliceRandom;
+use rand_chacha::ChaCha8Rng;
+
+use crate::seed_generation::{
+    game_world::{BossCharacter, GameWorld, Hubs},
+    item_randomization::{
+        item_pool::get_preplaced_items,
+        randomize_items::{get_final_item_placement, get_spheres},
+    },
+    randomization_datastructures::{ItemLocation, LevelID, RaceReward, RaceType},
+    seed_estimate::get_required_locations,
+    seed_settings::SeedSettings,
+};
+
+/// A hint shown by Aku Aku / Uka Uka instead of the vanilla mask hints
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hint {
+    /// The hub holding the n-th item of a kind in playthrough order. No
+    /// ordinal for items that only exist once.
+    ItemInHub {
+        item: RaceReward,
+        ordinal: Option<u8>,
+        hub: Hubs,
+    },
+    LocationNotRequired(ItemLocation),
+    ItemOnRaceType {
+        item: RaceReward,
+        racetype: RaceType,
+    },
+}
+
+impl Hint {
+    pub fn get_id(&self) -> u16 {
+        match self {
+            Hint::ItemInHub { .. } => 0,
+            Hint::LocationNotRequired(_) => 1,
+            Hint::ItemOnRaceType { .. } => 2,
+        }
+    }
+}
+
+impl std::fmt::Display for Hint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Hint::ItemInHub { item, ordinal: Some(x), hub } => {
+                let suffix = match (x % 100, x % 10) {
+                    (11..=13, _) => "th",
+                    (_, 1) => "st",
+                    (_, 2) => "nd",
+                    (_, 3) => "rd",
+                    _ => "th",
+                };
+                write!(f, "The {x}{suffix} {item} is in {hub}")
+            }
+            Hint::ItemInHub { item, ordinal: None, hub } => write!(f, "The {item} is in {hub}"),
+            Hint::LocationNotRequired(x) => write!(f, "{} {} is not required", x.levelid, x.racetype),
+            Hint::ItemOnRaceType { item, racetype } => write!(f, "A {item} is on a {racetype}"),
+        }
+    }
+}
+
+/// Get the hub whose warp pad or boss garage leads to this location
+pub fn get_hub(
+    location: &ItemLocation,
+    warppad_links: &HashMap<LevelID, LevelID>,
+    boss_hubs: &HashMap<BossCharacter, Hubs>,
+) -> Hubs {
+    if location.racetype == RaceType::BossRace {
+        if let Some(hub) = BossCharacter::from_levelid(location.levelid).and_then(|x| boss_hubs.get(&x)) {
+            return *hub;
+        }
+    }
+    let warppad_level = warppad_links
+        .iter()
+        .find(|(_, current_level)| **current_level == location.levelid)
+        .map_or(location.levelid, |(original_level, _)| *original_level);
+
+    match warppad_level {
+        LevelID::CrashCove
+        | LevelID::RoosTubes
+        | LevelID::MysteryCaves
+        | LevelID::SewerSpeedway
+        | LevelID::SkullRock => Hubs::NSanityBeach,
+        LevelID::TigerTemple
+        | LevelID::CocoPark
+        | LevelID::PapusPyramid
+        | LevelID::DingoCanyon
+        | LevelID::RampageRuins => Hubs::TheLostRuins,
+        LevelID::BlizzardBluff
+        | LevelID::DragonMines
+        | LevelID::PolarPass
+        | LevelID::TinyArena
+        | LevelID::RockyRoad => Hubs::GlacierPark,
+        LevelID::NGinLabs
+        | LevelID::CortexCastle
+        | LevelID::HotAirSkyway
+        | LevelID::OxideStation
+        | LevelID::NitroCourt => Hubs::CitadelCity,
+        LevelID::TurboTrack
+        | LevelID::SlideColiseum
+        | LevelID::CupRed
+        | LevelID::CupGreen
+        | LevelID::CupBlue
+        | LevelID::CupYellow
+        | LevelID::CupPurple => Hubs::GemStoneValley,
+    }
+}
+
+/// Pick hints about the final game world, as many of each kind as chosen.
+/// Nothing the player already knows gets hinted: unshuffled items, hand
+/// placed items, and excluded locations.
+pub fn get_hints(seed: &mut ChaCha8Rng, game_world: &GameWorld, chosen_settings: &SeedSettings) -> Vec<Hint> {
+    let hint_settings = chosen_settings.general.hints;
+    let Some(reward_shuffle) = chosen_settings.randomization.shuffle_race_rewards else {
+        return Vec::new();
+    };
+    if !chosen_settings.randomization.shuffle_adventure || hint_settings.get_total() == 0 {
+        return Vec::new();
+    }
+
+    let item_placement = get_final_item_placement(game_world, chosen_settings);
+    let warppad_links = game_world.get_warppad_links();
+    let boss_hubs = game_world.get_boss_hubs();
+    let known_locations: HashSet<ItemLocation> =
+        get_preplaced_items(&reward_shuffle, chosen_settings.force_vanilla_turbotrack())
+            .into_keys()
+            .chain(chosen_settings.general.item_plando.placements.keys().copied())
+            .collect();
+    let get_item = |location: &ItemLocation| item_placement[location].1;
+    let playthrough_order: Vec<ItemLocation> = get_spheres(&item_placement, chosen_settings)
+        .into_iter()
+        .flat_map(|x| x.locations)
+        .collect();
+    let required_locations = get_required_locations(game_world, chosen_settings);
+
+    let mut hints = Vec::new();
+
+    // Items of required locations, split between item area and race type hints
+    let mut required_item_locations: Vec<ItemLocation> = required_locations
+        .iter()
+        .filter(|x| {
+            !known_locations.contains(x)
+                && !matches!(get_item(x), None | Some(RaceReward::Nothing | RaceReward::BeatTheGame))
+        })
+        .copied()
+        .collect();
+    required_item_locations.shuffle(seed);
+    let mut required_item_locations = required_item_locations.into_iter();
+
+    // Hinting the same item kind for the same hub or race type twice tells
+    // the player little, so leave those out
+    let mut item_area_hints: Vec<(RaceReward, Hubs)> = Vec::new();
+    for location in required_item_locations.by_ref() {
+        if item_area_hints.len() >= usize::from(hint_settings.item_area_hints) {
+            break;
+        }
+        let item = get_item(&location).expect("checked by filter");
+        let hub = get_hub(&location, &warppad_links, &boss_hubs);
+        if item_area_hints.contains(&(item, hub)) {
+            continue;
+        }
+        item_area_hints.push((item, hub));
+
+        let same_items: Vec<&ItemLocation> = playthrough_order
+            .iter()
+            .filter(|x| get_item(x) == Some(item))
+            .collect();
+        let ordinal = if same_items.len() > 1 {
+            same_items
+                .iter()
+                .position(|x| **x == location)
+                .and_then(|x| u8::try_from(x + 1).ok())
+        } else {
+            None
+        };
+
+        hints.push(Hint::ItemInHub { item, ordinal, hub });
+    }
+
+    let mut race_type_hints: Vec<(RaceReward, RaceType)> = Vec::new();
+    for location in required_item_locations {
+        if race_type_hints.len() >= usize::from(hint_settings.race_type_hints) {
+            break;
+        }
+        let item = get_item(&location).expect("checked by filter");
+        if race_type_hints.contains(&(item, location.racetype)) {
+            continue;
+        }
+        race_type_hints.push((item, location.racetype));
+
+        hints.push(Hint::ItemOnRaceType { item, racetype: location.racetype });
+    }
+
+    // Locations the goal can be reached without
+    let mut unrequired_locations: Vec<ItemLocation> = item_placement
+        .keys()
+        .filter(|x| {
+            !required_locations.contains(x)
+                && !known_locations.contains(x)
+                && !chosen_settings.is_excluded_location(x)
+        })
+        .copied()
+        .collect();
+    unrequired_locations.sort();
+    unrequired_locations.shuffle(seed);
+
+    for location in unrequired_locations.into_iter().take(usize::from(hint_settings.not_required_hints)) {
+        hints.push(Hint::LocationNotRequired(location));
+    }
+
+    hints
+}
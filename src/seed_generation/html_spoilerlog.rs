@@ -0,0 +1,110 @@
+use std::{io, path::Path};
+
+use crate::seed_generation::spoilerlog::SEED_HASH_CHARACTERS;
+
+/// Seed hash icons, in the order of SEED_HASH_CHARACTERS. The UI loads the
+/// same files. PLACEHOLDERS: badges in the colors of the characters, until
+/// the title screen icons replace them, see res/hash_icons/README.md.
+pub const SEED_HASH_ICONS: [&str; 16] = [
+    include_str!("../../res/hash_icons/crash.svg"),
+    include_str!("../../res/hash_icons/cortex.svg"),
+    include_str!("../../res/hash_icons/tiny.svg"),
+    include_str!("../../res/hash_icons/coco.svg"),
+    include_str!("../../res/hash_icons/ngin.svg"),
+    include_str!("../../res/hash_icons/dingodile.svg"),
+    include_str!("../../res/hash_icons/polar.svg"),
+    include_str!("../../res/hash_icons/pura.svg"),
+    include_str!("../../res/hash_icons/pinstripe.svg"),
+    include_str!("../../res/hash_icons/papupapu.svg"),
+    include_str!("../../res/hash_icons/ripperroo.svg"),
+    include_str!("../../res/hash_icons/komodojoe.svg"),
+    include_str!("../../res/hash_icons/ntropy.svg"),
+    include_str!("../../res/hash_icons/penta.svg"),
+    include_str!("../../res/hash_icons/fakecrash.svg"),
+    include_str!("../../res/hash_icons/noxide.svg"),
+];
+
+const STYLE: &str = "body { font-family: sans-serif; margin: 2em; }\n\
+    .hash { display: flex; gap: 1em; }\n\
+    .hash figure { margin: 0; text-align: center; }\n\
+    table { border-collapse: collapse; }\n\
+    th, td { border: 1px solid #aaa; padding: 0.2em 0.5em; text-align: left; vertical-align: top; }\n\
+    ul { margin: 0; padding-left: 1.2em; }";
+
+/// Write the spoiler log as a web page next to its JSON, with the seed hash
+/// as icons
+pub fn write_html_spoilerlog(html_path: &Path, spoilerlog: &serde_json::Value) -> Result<(), io::Error> {
+    std::fs::write(html_path, get_html_spoilerlog(spoilerlog))
+}
+
+/// Render the spoiler log JSON as HTML: the seed hash icons first, then one
+/// section per entry of the spoiler log
+pub fn get_html_spoilerlog(spoilerlog: &serde_json::Value) -> String {
+    let mut html = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+            <title>CTR Randomizer Spoiler Log</title>\n<style>\n{STYLE}\n</style>\n</head>\n<body>\n\
+            <h1>CTR Randomizer Spoiler Log</h1>\n"
+    );
+
+    let seed_hash = spoilerlog["seed_hash"].as_str().unwrap_or_default();
+    html.push_str("<h2>Seed Hash</h2>\n<div class=\"hash\">\n");
+    for character in seed_hash.split(", ") {
+        match SEED_HASH_CHARACTERS.iter().position(|x| *x == character) {
+            Some(x) => html.push_str(&format!(
+                "<figure>{}<figcaption>{}</figcaption></figure>\n",
+                SEED_HASH_ICONS[x].trim(),
+                escape(character),
+            )),
+            None => html.push_str(&format!("<figure><figcaption>{}</figcaption></figure>\n", escape(character))),
+        }
+    }
+    html.push_str("</div>\n");
+
+    if let Some(entries) = spoilerlog.as_object() {
+        for (key, value) in entries.iter().filter(|(key, _)| *key != "seed_hash") {
+            html.push_str(&format!("<h2>{}</h2>\n{}\n", get_title(key), get_html_value(value)));
+        }
+    }
+
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+/// "hub_entrances" to "Hub entrances"
+fn get_title(key: &str) -> String {
+    let title = key.replace('_', " ");
+    let mut chars = title.chars();
+    match chars.next() {
+        Some(x) => escape(&(x.to_uppercase().collect::<String>() + chars.as_str())),
+        None => String::new(),
+    }
+}
+
+fn get_html_value(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::Object(entries) => format!(
+            "<table>{}</table>",
+            entries
+                .iter()
+                .map(|(key, value)| format!("<tr><th>{}</th><td>{}</td></tr>", escape(key), get_html_value(value)))
+                .collect::<String>(),
+        ),
+        serde_json::Value::Array(entries) if entries.is_empty() => "None".to_owned(),
+        serde_json::Value::Array(entries) => format!(
+            "<ul>{}</ul>",
+            entries
+                .iter()
+                .map(|x| format!("<li>{}</li>", get_html_value(x)))
+                .collect::<String>(),
+        ),
+        serde_json::Value::String(x) => escape(x),
+        x => escape(&x.to_string()),
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
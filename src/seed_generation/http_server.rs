@@ -0,0 +1,479 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    io::{BufRead, BufReader, Read, Write},
+    net::{TcpListener, TcpStream},
+    time::Duration,
+};
+
+use serde_json::json;
+
+use crate::seed_generation::{
+    output_paths::OutputSettings,
+    randomize_game::get_randomized_game,
+    rom_patching::{
+        base_patch::BasePatch,
+        bsdiff_patching::{apply_base_patchfile, create_patchfile, get_version_info_path},
+    },
+    seed_estimate::get_seed_estimate,
+    seed_gen_main::{get_random_seed, get_seed_rng},
+    seed_settings::SeedSettings,
+    spoilerlog::{get_seed_hash, get_spoilerlog},
+    write_rando_db::write_db_to_rom,
+};
+
+/// Largest request body accepted, settings strings are far smaller
+const MAX_BODY_SIZE: usize = 0x10000;
+
+/// How long a client may take to send its request or read the response,
+/// as requests are served one after another
+const CONNECTION_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Number of seeds kept in memory, the oldest seed is dropped first
+const MAX_SERVED_SEEDS: usize = 100;
+
+/// A seed generated by the server, kept in memory until the server stops
+struct ServedSeed {
+    patch: Vec<u8>,
+    /// None in race mode, the spoiler log of race seeds is never handed out
+    spoilerlog: Option<serde_json::Value>,
+}
+
+pub struct SeedServer {
+    rom_path: String,
+    /// If set, the spoiler log is only handed out with this bearer token
+    spoiler_token: Option<String>,
+    base_patch: BasePatch,
+    seeds: HashMap<u32, ServedSeed>,
+    /// Served seeds, oldest first
+    seed_order: VecDeque<u32>,
+}
+
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    /// Header names are lowercase
+    pub headers: HashMap<String, String>,
+    pub body: Vec<u8>,
+}
+
+pub struct Response {
+    pub status: u16,
+    pub content_type: &'static str,
+    pub body: Vec<u8>,
+}
+
+impl Response {
+    fn json(status: u16, body: &serde_json::Value) -> Self {
+        Response {
+            status,
+            content_type: "application/json",
+            body: body.to_string().into_bytes(),
+        }
+    }
+
+    fn error(status: u16, msg: &str) -> Self {
+        Response::json(status, &json!({ "error": msg }))
+    }
+}
+
+impl SeedServer {
+    pub fn new(rom_path: &str, spoiler_token: Option<String>) -> Self {
+        SeedServer {
+            rom_path: rom_path.to_owned(),
+            spoiler_token,
+            base_patch: BasePatch::builtin(),
+            seeds: HashMap::new(),
+            seed_order: VecDeque::new(),
+        }
+    }
+
+    /// Serve requests one after another on localhost, until the process ends.
+    ///
+    /// POST /seeds with `{"settings": "<settings string>", "seed": <optional>}`
+    /// generates a seed. GET /seeds/<seed>/patch returns its patch file, and
+    /// GET /seeds/<seed>/spoiler its spoiler log. Only the latest
+    /// MAX_SERVED_SEEDS seeds can be fetched.
+    pub fn run(&mut self, port: u16) -> Result<(), String> {
+        let listener = TcpListener::bind(("127.0.0.1", port))
+            .map_err(|_| format!("Could not listen on port {port}!"))?;
+        println!("Serving seeds on http://127.0.0.1:{port}");
+
+        self.serve(&listener);
+        Ok(())
+    }
+
+    fn serve(&mut self, listener: &TcpListener) {
+        for stream in listener.incoming().flatten() {
+            // A client hanging up early is not a reason to stop serving
+            let _ = self.handle_connection(stream);
+        }
+    }
+
+    fn handle_connection(&mut self, mut stream: TcpStream) -> std::io::Result<()> {
+        stream.set_read_timeout(Some(CONNECTION_TIMEOUT))?;
+        stream.set_write_timeout(Some(CONNECTION_TIMEOUT))?;
+
+        let response = match read_request(&mut stream) {
+            Ok(request) => self.handle_request(&request),
+            Err(msg) => Response::error(400, &msg),
+        };
+
+        write!(
+            stream,
+            "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+            response.status,
+            get_reason_phrase(response.status),
+            response.content_type,
+            response.body.len(),
+        )?;
+        stream.write_all(&response.body)?;
+        stream.flush()
+    }
+
+    pub fn handle_request(&mut self, request: &Request) -> Response {
+        let segments: Vec<&str> = request.path.trim_matches('/').split('/').collect();
+
+        match (request.method.as_str(), segments.as_slice()) {
+            ("POST", ["seeds"]) => self.post_seed(&request.body),
+            ("GET", ["seeds", seed, "patch"]) => match self.get_seed(seed) {
+                Some(x) => Response {
+                    status: 200,
+                    content_type: "application/octet-stream",
+                    body: x.patch.clone(),
+                },
+                None => Response::error(404, "Unknown seed!"),
+            },
+            ("GET", ["seeds", seed, "spoiler"]) => {
+                if let Some(token) = &self.spoiler_token {
+                    let authorization = request.headers.get("authorization").map(String::as_str);
+                    if authorization != Some(format!("Bearer {token}").as_str()) {
+                        return Response::error(401, "The spoiler log is locked!");
+                    }
+                }
+                match self.get_seed(seed).map(|x| &x.spoilerlog) {
+                    Some(Some(x)) => Response::json(200, x),
+                    Some(None) => Response::error(403, "Race mode seeds have no spoiler log!"),
+                    None => Response::error(404, "Unknown seed!"),
+                }
+            }
+            (_, ["seeds", ..]) => Response::error(405, "Method not allowed!"),
+            _ => Response::error(404, "Not found!"),
+        }
+    }
+
+    fn get_seed(&self, seed: &str) -> Option<&ServedSeed> {
+        self.seeds.get(&seed.parse().ok()?)
+    }
+
+    fn post_seed(&mut self, body: &[u8]) -> Response {
+        let request: serde_json::Value = match serde_json::from_slice(body) {
+            Ok(x) => x,
+            Err(_) => return Response::error(400, "The request body is not valid JSON!"),
+        };
+        let Some(settings_string) = request["settings"].as_str() else {
+            return Response::error(400, "The request is missing the 'settings' string!");
+        };
+        let chosen_settings = match SeedSettings::try_from(settings_string).and_then(|x| x.validate().map(|()| x)) {
+            Ok(x) => x,
+            Err(msg) => return Response::error(400, &msg),
+        };
+
+        let seed = match &request["seed"] {
+            serde_json::Value::Null => get_random_seed(),
+            // A chosen seed would let anyone generate the race seed again
+            _ if chosen_settings.race_mode => {
+                return Response::error(400, "The seed cannot be chosen in race mode!");
+            }
+            x => match x.as_u64().and_then(|x| u32::try_from(x).ok()).filter(|x| *x != 0) {
+                Some(x) => x,
+                None => return Response::error(400, "The seed has to be a number from 1 to 4294967295!"),
+            },
+        };
+
+        match self.generate(seed, &chosen_settings) {
+            Ok((seed, served_seed)) => {
+                let mut response = json!({
+                    "seed": seed,
+                    "seed_hash": get_seed_hash(seed),
+                    "settings": chosen_settings.to_string(),
+                    "patch": format!("/seeds/{seed}/patch"),
+                });
+                if served_seed.spoilerlog.is_some() {
+                    response["spoiler"] = json!(format!("/seeds/{seed}/spoiler"));
+                }
+                self.insert_seed(seed, served_seed);
+                Response::json(200, &response)
+            }
+            Err(msg) => Response::error(500, &msg),
+        }
+    }
+
+    fn insert_seed(&mut self, seed: u32, served_seed: ServedSeed) {
+        self.seed_order.retain(|x| *x != seed);
+        while self.seed_order.len() >= MAX_SERVED_SEEDS {
+            if let Some(oldest_seed) = self.seed_order.pop_front() {
+                self.seeds.remove(&oldest_seed);
+            }
+        }
+        self.seed_order.push_back(seed);
+        self.seeds.insert(seed, served_seed);
+    }
+
+    /// Generate a seed like the UI does, but only keep the patch file and
+    /// spoiler log instead of the patched ROM. Returns the seed that was
+    /// generated, which differs from the given seed in race mode.
+    fn generate(&self, seed: u32, chosen_settings: &SeedSettings) -> Result<(u32, ServedSeed), String> {
+        let (seed, rng) = get_seed_rng(seed, chosen_settings);
+        let randomized_game = get_randomized_game(rng, seed, chosen_settings)
+            .map_err(|_| format!("Failed to generate a randomized game for seed {seed}!"))?;
+        let seed_estimate = get_seed_estimate(&randomized_game, chosen_settings);
+
+        // The ROM and patch file are only written to read the patch back, so
+        // they go to the temp folder and never replace files of the player
+        let output_settings = OutputSettings { directory: Some(std::env::temp_dir()), ..Default::default() };
+        let new_rom_path = output_settings.get_new_rom_path(std::path::Path::new(&self.rom_path), seed)?;
+        let new_rom = apply_base_patchfile(&self.rom_path, &new_rom_path, &self.base_patch)
+            .map_err(|_| "Could not apply base patch to vanilla ROM!".to_owned())?;
+        let patch = write_db_to_rom(&new_rom, &randomized_game, &self.base_patch.manifest)
+            .map_err(str::to_owned)
+            .and_then(|()| create_patchfile(&self.rom_path, &new_rom, &self.base_patch).map_err(|_| "Could not create patch file!".to_owned()))
+            .and_then(|x| {
+                let patch = std::fs::read(&x).map_err(|_| "Could not read patch file!".to_owned());
+                let _ = std::fs::remove_file(get_version_info_path(&x));
+                let _ = std::fs::remove_file(x);
+                patch
+            });
+        let _ = std::fs::remove_file(new_rom);
+
+        let spoilerlog = (!chosen_settings.race_mode)
+            .then(|| get_spoilerlog(randomized_game, seed, chosen_settings, &seed_estimate, &self.base_patch));
+
+        Ok((seed, ServedSeed { patch: patch?, spoilerlog }))
+    }
+}
+
+fn read_request(stream: &mut TcpStream) -> Result<Request, String> {
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+    reader.read_line(&mut line).map_err(|_| "Could not read request!".to_owned())?;
+
+    let mut request_line = line.split_whitespace();
+    let (Some(method), Some(path)) = (request_line.next(), request_line.next()) else {
+        return Err("Invalid request line!".to_owned());
+    };
+    let (method, path) = (method.to_owned(), path.to_owned());
+
+    let mut headers = HashMap::new();
+    loop {
+        line.clear();
+        reader.read_line(&mut line).map_err(|_| "Could not read request!".to_owned())?;
+        let Some((name, value)) = line.trim_end().split_once(':') else {
+            break;
+        };
+        headers.insert(name.trim().to_lowercase(), value.trim().to_owned());
+    }
+
+    let body_size: usize = headers
+        .get("content-length")
+        .map_or(Ok(0), |x| x.parse())
+        .map_err(|_| "Invalid Content-Length!".to_owned())?;
+    if body_size > MAX_BODY_SIZE {
+        return Err("Request body too large!".to_owned());
+    }
+    let mut body = vec![0; body_size];
+    reader.read_exact(&mut body).map_err(|_| "Could not read request body!".to_owned())?;
+
+    Ok(Request { method, path, headers, body })
+}
+
+fn get_reason_phrase(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        _ => "Internal Server Error",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{Read, Write},
+        net::{TcpListener, TcpStream},
+    };
+
+    use super::*;
+    use crate::seed_generation::version::DB_VERSION;
+
+    /// Shuffled race rewards and warp pads, with random warp pad unlocks
+    const SETTINGS: &str = "rnd1;111;11;1;1;0;0;0;0;0;0;\ngen00112000;\ninv-;\nexc-;\nrlt0;-;\nhnt000000;\n\
+        hsh00000000;000;\npln-;\nqol10000;\ntrk002;\nwrt000;";
+
+    /// Server on a stand-in vanilla ROM, with a stand-in base mod given as
+    /// patched ROM, so seeds can be generated without the game
+    fn get_test_server(name: &str, spoiler_token: Option<&str>) -> SeedServer {
+        let directory = std::env::temp_dir().join(format!("ctr_http_server_test_{name}"));
+        std::fs::create_dir_all(&directory).unwrap();
+        let rom_path = directory.join("vanilla.bin");
+        std::fs::write(&rom_path, vec![0; 0x20000]).unwrap();
+        let base_rom_path = directory.join("base.bin");
+        std::fs::write(&base_rom_path, vec![0x11; 0x20000]).unwrap();
+        std::fs::write(
+            base_rom_path.with_extension("json"),
+            json!({ "db_version": DB_VERSION, "db_offset": "0x1000", "db_max_size": "0x1000" }).to_string(),
+        )
+        .unwrap();
+
+        let mut server = SeedServer::new(rom_path.to_str().unwrap(), spoiler_token.map(str::to_owned));
+        server.base_patch = BasePatch::from_file(&base_rom_path).unwrap();
+        server
+    }
+
+    fn get_request(method: &str, path: &str, headers: &[(&str, &str)], body: &str) -> Request {
+        Request {
+            method: method.to_owned(),
+            path: path.to_owned(),
+            headers: headers.iter().map(|(name, value)| ((*name).to_owned(), (*value).to_owned())).collect(),
+            body: body.as_bytes().to_vec(),
+        }
+    }
+
+    fn post_seed(server: &mut SeedServer, body: &serde_json::Value) -> Response {
+        server.handle_request(&get_request("POST", "/seeds", &[], &body.to_string()))
+    }
+
+    fn get_body(response: &Response) -> serde_json::Value {
+        serde_json::from_slice(&response.body).unwrap()
+    }
+
+    #[test]
+    fn post_seed_with_good_input() {
+        let mut server = get_test_server("post_seed", None);
+
+        let response = post_seed(&mut server, &json!({ "settings": SETTINGS, "seed": 1234 }));
+        assert_eq!(response.status, 200);
+        let body = get_body(&response);
+        assert_eq!(body["seed"], 1234);
+        assert_eq!(body["seed_hash"], get_seed_hash(1234));
+        assert_eq!(body["patch"], "/seeds/1234/patch");
+
+        let response = server.handle_request(&get_request("GET", "/seeds/1234/patch", &[], ""));
+        assert_eq!(response.status, 200);
+        assert_eq!(response.content_type, "application/octet-stream");
+        assert!(!response.body.is_empty());
+
+        let response = server.handle_request(&get_request("GET", "/seeds/1234/spoiler", &[], ""));
+        assert_eq!(response.status, 200);
+        assert_eq!(get_body(&response)["seed_hash"], get_seed_hash(1234));
+
+        let response = server.handle_request(&get_request("GET", "/seeds/4321/patch", &[], ""));
+        assert_eq!(response.status, 404);
+    }
+
+    #[test]
+    fn post_seed_with_bad_input() {
+        let mut server = SeedServer::new("/nonexistent.bin", None);
+
+        let response = server.handle_request(&get_request("POST", "/seeds", &[], "{\"settings\":"));
+        assert_eq!(response.status, 400);
+        assert_eq!(get_body(&response)["error"], "The request body is not valid JSON!");
+
+        let response = post_seed(&mut server, &json!({ "seed": 1234 }));
+        assert_eq!(response.status, 400);
+        assert_eq!(get_body(&response)["error"], "The request is missing the 'settings' string!");
+
+        let response = post_seed(&mut server, &json!({ "settings": SETTINGS, "seed": 0 }));
+        assert_eq!(response.status, 400);
+        assert_eq!(get_body(&response)["error"], "The seed has to be a number from 1 to 4294967295!");
+
+        let response = post_seed(&mut server, &json!({ "settings": "garbage", "seed": 1234 }));
+        assert_eq!(response.status, 400);
+
+        assert!(server.seeds.is_empty());
+    }
+
+    #[test]
+    fn spoiler_needs_token() {
+        let mut server = get_test_server("spoiler_token", Some("secret"));
+        assert_eq!(post_seed(&mut server, &json!({ "settings": SETTINGS, "seed": 5678 })).status, 200);
+
+        // The patch is never locked
+        let response = server.handle_request(&get_request("GET", "/seeds/5678/patch", &[], ""));
+        assert_eq!(response.status, 200);
+
+        let response = server.handle_request(&get_request("GET", "/seeds/5678/spoiler", &[], ""));
+        assert_eq!(response.status, 401);
+        let wrong_token = [("authorization", "Bearer guess")];
+        let response = server.handle_request(&get_request("GET", "/seeds/5678/spoiler", &wrong_token, ""));
+        assert_eq!(response.status, 401);
+
+        let token = [("authorization", "Bearer secret")];
+        let response = server.handle_request(&get_request("GET", "/seeds/5678/spoiler", &token, ""));
+        assert_eq!(response.status, 200);
+        assert_eq!(get_body(&response)["seed_hash"], get_seed_hash(5678));
+    }
+
+    #[test]
+    fn race_mode_seed() {
+        let mut server = get_test_server("race_mode", Some("secret"));
+        let settings = SETTINGS.replace("wrt000;", "wrt001;");
+
+        let response = post_seed(&mut server, &json!({ "settings": settings, "seed": 1234 }));
+        assert_eq!(response.status, 400);
+        assert_eq!(get_body(&response)["error"], "The seed cannot be chosen in race mode!");
+
+        let response = post_seed(&mut server, &json!({ "settings": settings }));
+        assert_eq!(response.status, 200);
+        let body = get_body(&response);
+        assert!(body.get("spoiler").is_none());
+        let seed = body["seed"].as_u64().unwrap();
+
+        let response = server.handle_request(&get_request("GET", &format!("/seeds/{seed}/patch"), &[], ""));
+        assert_eq!(response.status, 200);
+        let token = [("authorization", "Bearer secret")];
+        let response = server.handle_request(&get_request("GET", &format!("/seeds/{seed}/spoiler"), &token, ""));
+        assert_eq!(response.status, 403);
+    }
+
+    #[test]
+    fn drops_oldest_seed() {
+        let mut server = SeedServer::new("/nonexistent.bin", None);
+        for seed in 1..=MAX_SERVED_SEEDS as u32 + 1 {
+            server.insert_seed(seed, ServedSeed { patch: Vec::new(), spoilerlog: None });
+        }
+
+        assert_eq!(server.seeds.len(), MAX_SERVED_SEEDS);
+        assert!(server.get_seed("1").is_none());
+        assert!(server.get_seed("2").is_some());
+    }
+
+    #[test]
+    fn tcp_round_trip() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        std::thread::spawn(move || SeedServer::new("/nonexistent.bin", None).serve(&listener));
+
+        let send = |request: &str| -> String {
+            let mut stream = TcpStream::connect(address).unwrap();
+            stream.write_all(request.as_bytes()).unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            response
+        };
+
+        let body = "{\"seed\": 1}";
+        let response = send(&format!("POST /seeds HTTP/1.1\r\nContent-Length: {}\r\n\r\n{body}", body.len()));
+        let (head, body) = response.split_once("\r\n\r\n").unwrap();
+        assert!(head.starts_with("HTTP/1.1 400 Bad Request\r\n"));
+        assert!(head.contains("Content-Type: application/json"));
+        assert!(head.contains(&format!("Content-Length: {}", body.len())));
+        let body: serde_json::Value = serde_json::from_str(body).unwrap();
+        assert_eq!(body["error"], "The request is missing the 'settings' string!");
+
+        let response = send("GET /seeds/1/patch HTTP/1.1\r\n\r\n");
+        assert!(response.starts_with("HTTP/1.1 404 Not Found\r\n"));
+    }
+}
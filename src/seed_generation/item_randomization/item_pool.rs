@@ -0,0 +1,214 @@
+use std::collections::HashMap;
+
+use crate::seed_generation::{
+    randomization_datastructures::{ItemLocation, LevelID, RaceReward, RaceType},
+    seed_settings::RewardShuffle,
+};
+
+/// Build the list of items to place. Items the player starts out with are
+/// replaced by filler, so that every location still gets an item.
+pub fn build_item_pool(
+    reward_shuffle: &RewardShuffle,
+    force_vanilla_turbotrack: bool,
+    starting_items: &[RaceReward],
+) -> Vec<RaceReward> {
+    let mut item_pool: Vec<RaceReward> = vec![
+        // 16 Trophies
+        RaceReward::Trophy,
+        RaceReward::Trophy,
+        RaceReward::Trophy,
+        RaceReward::Trophy,
+        RaceReward::Trophy,
+        RaceReward::Trophy,
+        RaceReward::Trophy,
+        RaceReward::Trophy,
+        RaceReward::Trophy,
+        RaceReward::Trophy,
+        RaceReward::Trophy,
+        RaceReward::Trophy,
+        RaceReward::Trophy,
+        RaceReward::Trophy,
+        RaceReward::Trophy,
+        RaceReward::Trophy,
+        // 5 x 4 CTR Tokens
+        RaceReward::RedCtrToken,
+        RaceReward::RedCtrToken,
+        RaceReward::RedCtrToken,
+        RaceReward::RedCtrToken,
+        RaceReward::GreenCtrToken,
+        RaceReward::GreenCtrToken,
+        RaceReward::GreenCtrToken,
+        RaceReward::GreenCtrToken,
+        RaceReward::BlueCtrToken,
+        RaceReward::BlueCtrToken,
+        RaceReward::BlueCtrToken,
+        RaceReward::BlueCtrToken,
+        RaceReward::YellowCtrToken,
+        RaceReward::YellowCtrToken,
+        RaceReward::YellowCtrToken,
+        RaceReward::YellowCtrToken,
+        RaceReward::PurpleCtrToken,
+        RaceReward::PurpleCtrToken,
+        RaceReward::PurpleCtrToken,
+        RaceReward::PurpleCtrToken,
+        // 17 Sapphire & 17 Gold Relics (1 of each are Turbo Track special-cased)
+        RaceReward::SapphireRelic,
+        RaceReward::SapphireRelic,
+        RaceReward::SapphireRelic,
+        RaceReward::SapphireRelic,
+        RaceReward::SapphireRelic,
+        RaceReward::SapphireRelic,
+        RaceReward::SapphireRelic,
+        RaceReward::SapphireRelic,
+        RaceReward::SapphireRelic,
+        RaceReward::SapphireRelic,
+        RaceReward::SapphireRelic,
+        RaceReward::SapphireRelic,
+        RaceReward::SapphireRelic,
+        RaceReward::SapphireRelic,
+        RaceReward::SapphireRelic,
+        RaceReward::SapphireRelic,
+        RaceReward::SapphireRelic,
+        RaceReward::GoldRelic,
+        RaceReward::GoldRelic,
+        RaceReward::GoldRelic,
+        RaceReward::GoldRelic,
+        RaceReward::GoldRelic,
+        RaceReward::GoldRelic,
+        RaceReward::GoldRelic,
+        RaceReward::GoldRelic,
+        RaceReward::GoldRelic,
+        RaceReward::GoldRelic,
+        RaceReward::GoldRelic,
+        RaceReward::GoldRelic,
+        RaceReward::GoldRelic,
+        RaceReward::GoldRelic,
+        RaceReward::GoldRelic,
+        RaceReward::GoldRelic,
+        RaceReward::GoldRelic,
+    ];
+
+    if reward_shuffle.include_keys {
+        for _ in 0..4 {
+            item_pool.push(RaceReward::Key);
+        }
+    }
+
+    if reward_shuffle.include_gems {
+        item_pool.push(RaceReward::RedGem);
+        item_pool.push(RaceReward::GreenGem);
+        item_pool.push(RaceReward::BlueGem);
+        item_pool.push(RaceReward::YellowGem);
+        item_pool.push(RaceReward::PurpleGem);
+    }
+
+    if !force_vanilla_turbotrack {
+        item_pool.push(RaceReward::SapphireRelic);
+        item_pool.push(RaceReward::GoldRelic);
+    }
+
+    if reward_shuffle.include_platinum_relics {
+        for _ in 0..17 {
+            item_pool.push(RaceReward::PlatinumRelic);
+        }
+        if !force_vanilla_turbotrack {
+            item_pool.push(RaceReward::PlatinumRelic);
+        }
+    }
+
+    for starting_item in starting_items {
+        if let Some(index) = item_pool.iter().position(|x| x == starting_item) {
+            item_pool[index] = RaceReward::Nothing;
+        }
+    }
+
+    item_pool
+}
+
+/// Get the items placed before shuffling: ones that cannot be shuffled, and
+/// ones that the player does not want shuffled.
+pub fn get_preplaced_items(
+    reward_shuffle: &RewardShuffle,
+    force_vanilla_turbotrack: bool,
+) -> HashMap<ItemLocation, RaceReward> {
+    let mut preplaced_items = HashMap::new();
+
+    // Items that cannot be shuffled
+    preplaced_items.insert(
+        ItemLocation{levelid: LevelID::OxideStation, racetype: RaceType::BossRace},
+        RaceReward::BeatTheGame,
+    );
+    // Items that the player does not want shuffled
+    if !reward_shuffle.include_platinum_relics {
+        for level_id in [
+            // N.Sanity Beach
+            LevelID::CrashCove,
+            LevelID::RoosTubes,
+            LevelID::MysteryCaves,
+            LevelID::SewerSpeedway,
+            // The Lost Ruins
+            LevelID::TigerTemple,
+            LevelID::CocoPark,
+            LevelID::PapusPyramid,
+            LevelID::DingoCanyon,
+            // Glacier Park
+            LevelID::BlizzardBluff,
+            LevelID::DragonMines,
+            LevelID::PolarPass,
+            LevelID::TinyArena,
+            // Citadel City
+            LevelID::NGinLabs,
+            LevelID::CortexCastle,
+            LevelID::HotAirSkyway,
+            LevelID::OxideStation,
+            // Gem Stone Valley
+            LevelID::TurboTrack,
+            LevelID::SlideColiseum,
+        ] {
+            preplaced_items.insert(
+                ItemLocation{levelid: level_id, racetype: RaceType::RelicRacePlatinum},
+                RaceReward::PlatinumRelic,
+            );
+        }
+    } else if force_vanilla_turbotrack {
+        preplaced_items.insert(
+            ItemLocation{levelid: LevelID::TurboTrack, racetype: RaceType::RelicRacePlatinum},
+            RaceReward::PlatinumRelic,
+        );
+    }
+
+    if force_vanilla_turbotrack {
+        preplaced_items.insert(
+            ItemLocation{levelid: LevelID::TurboTrack, racetype: RaceType::RelicRaceSapphire},
+            RaceReward::SapphireRelic,
+        );
+        preplaced_items.insert(
+            ItemLocation{levelid: LevelID::TurboTrack, racetype: RaceType::RelicRaceGold},
+            RaceReward::GoldRelic,
+        );
+    }
+
+    if !reward_shuffle.include_gems {
+        preplaced_items.insert(ItemLocation{levelid: LevelID::CupRed, racetype: RaceType::GemCup}, RaceReward::RedGem);
+        preplaced_items.insert(ItemLocation{levelid: LevelID::CupGreen, racetype: RaceType::GemCup}, RaceReward::GreenGem);
+        preplaced_items.insert(ItemLocation{levelid: LevelID::CupBlue, racetype: RaceType::GemCup}, RaceReward::BlueGem);
+        preplaced_items.insert(ItemLocation{levelid: LevelID::CupYellow, racetype: RaceType::GemCup}, RaceReward::YellowGem);
+        preplaced_items.insert(ItemLocation{levelid: LevelID::CupPurple, racetype: RaceType::GemCup}, RaceReward::PurpleGem);
+    }
+
+    if !reward_shuffle.include_keys {
+        for level_id in [
+            LevelID::RoosTubes,
+            LevelID::PapusPyramid,
+            LevelID::DragonMines,
+            LevelID::HotAirSkyway,
+        ] {
+            preplaced_items.insert(
+                ItemLocation{levelid: level_id, racetype: RaceType::BossRace},
+                RaceReward::Key,
+            );
+        }
+    }
+
+    preplaced_items
+}
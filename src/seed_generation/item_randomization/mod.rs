@@ -1,2 +1,3 @@
+pub mod item_pool;
 pub mod player_inventory;
 pub mod randomize_items;
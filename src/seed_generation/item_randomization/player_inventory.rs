@@ -1,7 +1,8 @@
 use std::collections::HashMap;
 
-use crate::seed_generation::randomization_datastructures::{
-    LevelID, RaceReward, RequiredItem, UnlockRequirement
+use crate::seed_generation::{
+    randomization_datastructures::{LevelID, RaceReward, RequiredItem, UnlockRequirement, UNLOCKABLES},
+    tricks::Trick,
 };
 
 #[derive(Debug)]
@@ -18,8 +19,10 @@ pub struct PlayerInventory {
     num_keys: u8,
     collected_gems: GemFlags,
     beaten_tracks: Vec<LevelID>,
-    //unlocked_tracks: TrackUnlocks,
-    //unlocked_characters: CharacterUnlocks,
+    /// Unlocked characters, arcade tracks and battle arenas
+    unlocks: Vec<RaceReward>,
+    /// Tricks the player is expected to know
+    tricks: Vec<Trick>,
     //unlocked_cups: CupUnlocks,
 }
 
@@ -44,6 +47,15 @@ impl PlayerInventory {
                 purple_gem: false,
             },
             beaten_tracks: Vec::new(),
+            unlocks: Vec::new(),
+            tricks: Vec::new(),
+        }
+    }
+
+    pub fn with_tricks(tricks: Vec<Trick>) -> Self {
+        PlayerInventory {
+            tricks,
+            ..PlayerInventory::new()
         }
     }
 
@@ -70,6 +82,10 @@ impl PlayerInventory {
         all_items.insert(RaceReward::YellowGem, self.collected_gems.yellow_gem.into());
         all_items.insert(RaceReward::PurpleGem, self.collected_gems.purple_gem.into());
 
+        for unlock in UNLOCKABLES {
+            all_items.insert(unlock, self.unlocks.contains(&unlock).into());
+        }
+
         all_items
     }
 
@@ -132,7 +148,25 @@ impl PlayerInventory {
             | RaceReward::PurpleGem => {
                 self.collected_gems.set_gem(new_item);
             }
-            RaceReward::BeatTheGame => {}
+            RaceReward::RipperRooUnlock
+            | RaceReward::PapuPapuUnlock
+            | RaceReward::KomodoJoeUnlock
+            | RaceReward::PinstripeUnlock
+            | RaceReward::FakeCrashUnlock
+            | RaceReward::NTropyUnlock
+            | RaceReward::PentaPenguinUnlock
+            | RaceReward::NOxideUnlock
+            | RaceReward::SlideColiseumUnlock
+            | RaceReward::TurboTrackUnlock
+            | RaceReward::SkullRockUnlock
+            | RaceReward::RampageRuinsUnlock
+            | RaceReward::RockyRoadUnlock
+            | RaceReward::NitroCourtUnlock => {
+                if !self.unlocks.contains(&new_item) {
+                    self.unlocks.push(new_item);
+                }
+            }
+            RaceReward::BeatTheGame | RaceReward::Nothing => {}
         }
     }
 
@@ -142,6 +176,10 @@ impl PlayerInventory {
         }
     }
 
+    pub fn get_beaten_tracks(&self) -> &[LevelID] {
+        &self.beaten_tracks
+    }
+
     pub fn does_pass_requirements(&self, requirements: &Vec<UnlockRequirement>) -> bool {
         let mut passes_all_requirements = true;
 
@@ -165,6 +203,7 @@ impl PlayerInventory {
                         RequiredItem::SapphireRelic => self.num_relics_sapphire,
                         RequiredItem::GoldRelic => self.num_relics_gold,
                         RequiredItem::PlatinumRelic => self.num_relics_platinum,
+                        RequiredItem::GoldOrPlatinumRelic => self.num_relics_gold + self.num_relics_platinum,
                         RequiredItem::AnyRelic => {
                             self.num_relics_sapphire + self.num_relics_gold + self.num_relics_platinum
                         }
@@ -190,6 +229,12 @@ impl PlayerInventory {
                         }
                     }
                 }
+                UnlockRequirement::Trick(trick) => {
+                    if !self.tricks.contains(trick) {
+                        passes_all_requirements = false;
+                        break;
+                    }
+                }
             }
         }
 
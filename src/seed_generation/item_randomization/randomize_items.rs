@@ -1,47 +1,43 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 use rand::seq::{IndexedRandom, SliceRandom};
 use rand_chacha::ChaCha8Rng;
 
 use crate::seed_generation::{
-    game_world::{BossCharacter, Hubs},
-    item_randomization::player_inventory::PlayerInventory,
+    game_world::{BossCharacter, GameWorld, Hubs, BOSS_CHARACTERS},
+    item_randomization::{
+        item_pool::{build_item_pool, get_preplaced_items},
+        player_inventory::PlayerInventory,
+    },
     randomization_datastructures::{
-        LevelID, ItemLocation, RaceReward, RaceType, RequiredItem, UnlockRequirement, UnlockRequirementItem, UnlockStage
+        LevelID, ItemLocation, RaceReward, RaceType, RequiredItem, UnlockRequirement, UnlockRequirementItem, UnlockStage,
+        UNLOCKABLES,
     },
-    seed_settings::RewardShuffle,
+    seed_settings::{Goal, RewardShuffle, SeedSettings},
+    tricks::TRICK_REQUIREMENTS,
 };
 
-/// Generate item pool, create a location list to get logical requirements,
-/// then run up to 1000 item placement attempts.
-/// Returns placement result or Err.
+/// Generate item pool, then run up to 1000 item placement attempts on the
+/// given location list (see `get_location_list`).
+/// Returns placement result and the number of attempts it took, or Err.
 pub fn get_shuffled_rewards(
     seed: &mut ChaCha8Rng,
+    chosen_settings: &SeedSettings,
     reward_shuffle: &RewardShuffle,
     force_vanilla_turbotrack: bool,
-    warppad_links: &HashMap<LevelID, LevelID>,
-    warppad_unlocks: HashMap<(LevelID, UnlockStage), Option<UnlockRequirementItem>>,
-    bossgarage_requirements: HashMap<BossCharacter, UnlockRequirement>,
-    hub_requirements: HashMap<Hubs, Option<UnlockRequirementItem>>,
+    location_list: &HashMap<ItemLocation, Vec<UnlockRequirement>>,
     shuffled_warppad_requirements: bool,
-) -> Result<HashMap<ItemLocation, RaceReward>, String> {
+) -> Result<(HashMap<ItemLocation, RaceReward>, u32), String> {
     // generate item pool, based on
     // * include_keys
     // * include_gems
     // * include_platinum_relics
+    // * starting inventory
+    let starting_items = chosen_settings.general.starting_inventory.get_items();
     let item_pool = build_item_pool(
         reward_shuffle,
         force_vanilla_turbotrack,
-    );
-
-    // generate logical requirements from warppad links, warppad_unlocks, hub requirements, and garage unlocks
-    // The warppad_links here are VanillaTrackLocation: ActualTrack
-    // The warppad_unlocks here are (ActualTrack, UnlockStage, Option<UnlockRequirement>)
-    let location_list = get_location_list(
-        warppad_links,
-        warppad_unlocks,
-        bossgarage_requirements,
-        hub_requirements,
+        &starting_items,
     );
 
     // run and return item placement
@@ -49,6 +45,7 @@ pub fn get_shuffled_rewards(
     for attempts in 1..num_max_attempts+1 {
         let placement_result = get_item_placement(
             seed,
+            chosen_settings,
             item_pool.clone(),
             reward_shuffle,
             force_vanilla_turbotrack,
@@ -57,7 +54,7 @@ pub fn get_shuffled_rewards(
         );
         if let Ok(x) = placement_result {
             println!("Item placement needed {attempts} attempts.");
-            return Ok(x);
+            return Ok((x, attempts));
         }
     }
     let err_text = format!("Item placement failed after {num_max_attempts} attempts.");
@@ -65,122 +62,80 @@ pub fn get_shuffled_rewards(
     Err(err_text)
 }
 
-pub fn build_item_pool(
-    reward_shuffle: &RewardShuffle,
-    force_vanilla_turbotrack: bool,
-) -> Vec<RaceReward> {
-    let mut item_pool: Vec<RaceReward> = vec![
-        // 16 Trophies
-        RaceReward::Trophy,
-        RaceReward::Trophy,
-        RaceReward::Trophy,
-        RaceReward::Trophy,
-        RaceReward::Trophy,
-        RaceReward::Trophy,
-        RaceReward::Trophy,
-        RaceReward::Trophy,
-        RaceReward::Trophy,
-        RaceReward::Trophy,
-        RaceReward::Trophy,
-        RaceReward::Trophy,
-        RaceReward::Trophy,
-        RaceReward::Trophy,
-        RaceReward::Trophy,
-        RaceReward::Trophy,
-        // 5 x 4 CTR Tokens
-        RaceReward::RedCtrToken,
-        RaceReward::RedCtrToken,
-        RaceReward::RedCtrToken,
-        RaceReward::RedCtrToken,
-        RaceReward::GreenCtrToken,
-        RaceReward::GreenCtrToken,
-        RaceReward::GreenCtrToken,
-        RaceReward::GreenCtrToken,
-        RaceReward::BlueCtrToken,
-        RaceReward::BlueCtrToken,
-        RaceReward::BlueCtrToken,
-        RaceReward::BlueCtrToken,
-        RaceReward::YellowCtrToken,
-        RaceReward::YellowCtrToken,
-        RaceReward::YellowCtrToken,
-        RaceReward::YellowCtrToken,
-        RaceReward::PurpleCtrToken,
-        RaceReward::PurpleCtrToken,
-        RaceReward::PurpleCtrToken,
-        RaceReward::PurpleCtrToken,
-        // 17 Sapphire & 17 Gold Relics (1 of each are Turbo Track special-cased)
-        RaceReward::SapphireRelic,
-        RaceReward::SapphireRelic,
-        RaceReward::SapphireRelic,
-        RaceReward::SapphireRelic,
-        RaceReward::SapphireRelic,
-        RaceReward::SapphireRelic,
-        RaceReward::SapphireRelic,
-        RaceReward::SapphireRelic,
-        RaceReward::SapphireRelic,
-        RaceReward::SapphireRelic,
-        RaceReward::SapphireRelic,
-        RaceReward::SapphireRelic,
-        RaceReward::SapphireRelic,
-        RaceReward::SapphireRelic,
-        RaceReward::SapphireRelic,
-        RaceReward::SapphireRelic,
-        RaceReward::SapphireRelic,
-        RaceReward::GoldRelic,
-        RaceReward::GoldRelic,
-        RaceReward::GoldRelic,
-        RaceReward::GoldRelic,
-        RaceReward::GoldRelic,
-        RaceReward::GoldRelic,
-        RaceReward::GoldRelic,
-        RaceReward::GoldRelic,
-        RaceReward::GoldRelic,
-        RaceReward::GoldRelic,
-        RaceReward::GoldRelic,
-        RaceReward::GoldRelic,
-        RaceReward::GoldRelic,
-        RaceReward::GoldRelic,
-        RaceReward::GoldRelic,
-        RaceReward::GoldRelic,
-        RaceReward::GoldRelic,
-    ];
-
-    if reward_shuffle.include_keys {
-        for _ in 0..4 {
-            item_pool.push(RaceReward::Key);
-        }
-    }
-
-    if reward_shuffle.include_gems {
-        item_pool.push(RaceReward::RedGem);
-        item_pool.push(RaceReward::GreenGem);
-        item_pool.push(RaceReward::BlueGem);
-        item_pool.push(RaceReward::YellowGem);
-        item_pool.push(RaceReward::PurpleGem);
+/// Swap the filler left behind by the starting inventory for unlockables, in
+/// random order. Unlockables without filler to replace are handed out on top
+/// of race rewards later on, see `get_bonus_unlockables`.
+fn add_unlockables(seed: &mut ChaCha8Rng, item_pool: &mut [RaceReward]) {
+    let mut unlockables = UNLOCKABLES.to_vec();
+    unlockables.shuffle(seed);
+
+    for (item, unlockable) in item_pool
+        .iter_mut()
+        .filter(|x| matches!(x, RaceReward::Nothing))
+        .zip(unlockables)
+    {
+        *item = unlockable;
     }
+}
 
-    if !force_vanilla_turbotrack {
-        item_pool.push(RaceReward::SapphireRelic);
-        item_pool.push(RaceReward::GoldRelic);
-    }
+/// Hand out every unlockable that did not replace any filler on top of the
+/// reward of a random race, one unlockable per race. The race that beats the
+/// game and races already rewarding an unlockable are left out.
+pub fn get_bonus_unlockables(
+    seed: &mut ChaCha8Rng,
+    race_rewards: &HashMap<ItemLocation, RaceReward>,
+) -> BTreeMap<ItemLocation, RaceReward> {
+    let mut locations: Vec<ItemLocation> = race_rewards
+        .iter()
+        .filter(|(_, reward)| !matches!(reward, RaceReward::BeatTheGame) && !UNLOCKABLES.contains(reward))
+        .map(|(location, _)| *location)
+        .collect();
+    locations.sort();
+    locations.shuffle(seed);
+
+    UNLOCKABLES
+        .into_iter()
+        .filter(|x| !race_rewards.values().any(|reward| reward == x))
+        .zip(locations)
+        .map(|(unlockable, location)| (location, unlockable))
+        .collect()
+}
 
-    if reward_shuffle.include_platinum_relics {
-        for _ in 0..17 {
-            item_pool.push(RaceReward::PlatinumRelic);
-        }
-        if !force_vanilla_turbotrack {
-            item_pool.push(RaceReward::PlatinumRelic);
+/// Index of a non-filler item in the item pool that can be taken out while
+/// the kept items and the rest of the item pool still meet every requirement
+fn get_surplus_item_index(
+    chosen_settings: &SeedSettings,
+    kept_items: &[RaceReward],
+    item_pool: &[RaceReward],
+    all_requirements: &[UnlockRequirement],
+) -> Option<usize> {
+    (0..item_pool.len()).filter(|i| !item_pool[*i].is_filler()).find(|i| {
+        let mut inventory = PlayerInventory::with_tricks(chosen_settings.tricks.get_enabled_tricks());
+        for (j, item) in kept_items.iter().chain(item_pool).enumerate() {
+            if j != kept_items.len() + i {
+                inventory.add_item(*item);
+            }
         }
-    }
-
-    item_pool
+        all_requirements
+            .iter()
+            .all(|x| inventory.does_pass_requirements(&vec![x.clone()]))
+    })
 }
 
+/// Generate logical requirements for every item location from warppad links,
+/// warppad unlocks, hub requirements, and garage unlocks.
+/// The boss_hubs here are Boss: Hub of the garage the boss is behind
+/// The warppad_links here are VanillaTrackLocation: ActualTrack
+/// The warppad_unlocks here are (ActualTrack, UnlockStage, Option<UnlockRequirement>)
+/// The final challenge requirement gets added on top of N. Oxide's garage
+/// requirements, as beating his final challenge is what beats the game.
 pub fn get_location_list(
     warppad_links: &HashMap<LevelID, LevelID>,
     warppad_unlocks: HashMap<(LevelID, UnlockStage), Option<UnlockRequirementItem>>,
     bossgarage_requirements: HashMap<BossCharacter, UnlockRequirement>,
     hub_requirements: HashMap<Hubs, Option<UnlockRequirementItem>>,
+    boss_hubs: &HashMap<BossCharacter, Hubs>,
+    final_challenge_requirement: UnlockRequirementItem,
 ) -> HashMap<ItemLocation, Vec<UnlockRequirement>> {
     let mut location_list = HashMap::new();
 
@@ -273,36 +228,23 @@ pub fn get_location_list(
         location_list: &mut HashMap<ItemLocation, Vec<UnlockRequirement>>,
         bossgarage_requirements: &HashMap<BossCharacter, UnlockRequirement>,
         hub_requirements: &HashMap<Hubs, Option<UnlockRequirementItem>>,
-        level_id: LevelID,
+        boss_hubs: &HashMap<BossCharacter, Hubs>,
+        boss: BossCharacter,
     ) {
         let mut req_list = vec![bossgarage_requirements
-            .get(match level_id {
-                LevelID::RoosTubes => &BossCharacter::RipperRoo,
-                LevelID::PapusPyramid => &BossCharacter::PapuPapu,
-                LevelID::DragonMines => &BossCharacter::KomodoJoe,
-                LevelID::HotAirSkyway => &BossCharacter::Pinstripe,
-                LevelID::OxideStation => &BossCharacter::NOxide,
-                _ => panic!("should never happen"),
-            })
+            .get(&boss)
             .unwrap()
             .clone()];
 
         let hub_req = hub_requirements
-            .get(match level_id {
-                LevelID::RoosTubes => &Hubs::NSanityBeach,
-                LevelID::PapusPyramid => &Hubs::TheLostRuins,
-                LevelID::DragonMines => &Hubs::GlacierPark,
-                LevelID::HotAirSkyway => &Hubs::CitadelCity,
-                LevelID::OxideStation => &Hubs::GemStoneValley,
-                _ => panic!("should never happen"),
-            })
+            .get(boss_hubs.get(&boss).expect("every boss is behind a garage"))
             .unwrap();
 
         if let Some(x) = hub_req {
             req_list.push(UnlockRequirement::Item(*x));
         }
 
-        location_list.insert(ItemLocation{levelid: level_id, racetype: RaceType::BossRace}, req_list);
+        location_list.insert(ItemLocation{levelid: boss.get_levelid(), racetype: RaceType::BossRace}, req_list);
     }
 
     for (original_level, current_level) in warppad_links {
@@ -462,49 +404,68 @@ pub fn get_location_list(
         }
     }
 
-    // Boss garages
-    insert_boss_garage(
-        &mut location_list,
-        &bossgarage_requirements,
-        &hub_requirements,
-        LevelID::RoosTubes,
-    );
-    insert_boss_garage(
-        &mut location_list,
-        &bossgarage_requirements,
-        &hub_requirements,
-        LevelID::PapusPyramid,
-    );
-    insert_boss_garage(
-        &mut location_list,
-        &bossgarage_requirements,
-        &hub_requirements,
-        LevelID::DragonMines,
-    );
-    insert_boss_garage(
-        &mut location_list,
-        &bossgarage_requirements,
-        &hub_requirements,
-        LevelID::HotAirSkyway,
+    // Boss garages, every boss race is named after the boss' own track
+    for boss in BOSS_CHARACTERS {
+        insert_boss_garage(
+            &mut location_list,
+            &bossgarage_requirements,
+            &hub_requirements,
+            boss_hubs,
+            boss,
+        );
+    }
+    location_list
+        .get_mut(&ItemLocation{levelid: LevelID::OxideStation, racetype: RaceType::BossRace})
+        .expect("inserted right above")
+        .push(UnlockRequirement::Item(final_challenge_requirement));
+
+    // Relic races that need a trick on top
+    for (levelid, racetype, trick) in TRICK_REQUIREMENTS {
+        if let Some(requirements) = location_list.get_mut(&ItemLocation{levelid, racetype}) {
+            requirements.push(UnlockRequirement::Trick(trick));
+        }
+    }
+
+    location_list
+}
+
+/// Rebuild the requirements and items of every location from a finished
+/// game world, in the shape used during item placement.
+pub fn get_final_item_placement(
+    game_world: &GameWorld,
+    chosen_settings: &SeedSettings,
+) -> HashMap<ItemLocation, (Vec<UnlockRequirement>, Option<RaceReward>)> {
+    let location_list = get_location_list(
+        &game_world.get_warppad_links(),
+        game_world.get_warppad_unlocks(),
+        game_world.get_garage_unlocks(),
+        game_world.get_hub_requirements(),
+        &game_world.get_boss_hubs(),
+        chosen_settings.general.oxide_final_challenge_unlock.get_requirement(),
     );
-    insert_boss_garage(
-        &mut location_list,
-        &bossgarage_requirements,
-        &hub_requirements,
-        LevelID::OxideStation,
+    let mut race_rewards = game_world.get_race_rewards();
+    // Beating the game is not stored as a reward of Oxide's garage
+    race_rewards.insert(
+        ItemLocation{levelid: LevelID::OxideStation, racetype: RaceType::BossRace},
+        RaceReward::BeatTheGame,
     );
 
     location_list
+        .into_iter()
+        .map(|(location, requirements)| (location, (requirements, race_rewards.get(&location).copied())))
+        .collect()
 }
 
 fn get_item_placement(
     seed: &mut ChaCha8Rng,
+    chosen_settings: &SeedSettings,
     mut item_pool: Vec<RaceReward>,
     reward_shuffle: &RewardShuffle,
     force_vanilla_turbotrack: bool,
     location_list: HashMap<ItemLocation, Vec<UnlockRequirement>>,
     shuffled_warppad_requirements: bool,
 ) -> Result<HashMap<ItemLocation, RaceReward>, String> {
+    let starting_items = chosen_settings.general.starting_inventory.get_items();
     let mut item_placement: HashMap<
         ItemLocation,
         (Vec<UnlockRequirement>, Option<RaceReward>),
@@ -515,116 +476,72 @@ fn get_item_placement(
         item_placement.insert(k, (v, None));
     }
 
-    // Pre-place items that cannot be shuffled
-    item_placement
-        .get_mut(&ItemLocation{levelid: LevelID::OxideStation, racetype: RaceType::BossRace})
-        .unwrap()
-        .1 = Some(RaceReward::BeatTheGame);
-    // Pre-place items that the player does not want shuffled
-    if !reward_shuffle.include_platinum_relics {
-        for level_id in [
-            // N.Sanity Beach
-            LevelID::CrashCove,
-            LevelID::RoosTubes,
-            LevelID::MysteryCaves,
-            LevelID::SewerSpeedway,
-            // The Lost Ruins
-            LevelID::TigerTemple,
-            LevelID::CocoPark,
-            LevelID::PapusPyramid,
-            LevelID::DingoCanyon,
-            // Glacier Park
-            LevelID::BlizzardBluff,
-            LevelID::DragonMines,
-            LevelID::PolarPass,
-            LevelID::TinyArena,
-            // Citadel City
-            LevelID::NGinLabs,
-            LevelID::CortexCastle,
-            LevelID::HotAirSkyway,
-            LevelID::OxideStation,
-            // Gem Stone Valley
-            LevelID::TurboTrack,
-            LevelID::SlideColiseum,
-        ] {
-            item_placement
-                .get_mut(&ItemLocation {
-                    levelid: level_id,
-                    racetype: RaceType::RelicRacePlatinum,
-                })
-                .unwrap()
-                .1 = Some(RaceReward::PlatinumRelic);
+    // Pre-place items that cannot or should not be shuffled, followed by the
+    // items the player placed by hand
+    let item_plando = &chosen_settings.general.item_plando.placements;
+    let preplaced_items = get_preplaced_items(reward_shuffle, force_vanilla_turbotrack);
+    for (location, item) in preplaced_items.iter().chain(item_plando) {
+        item_placement.get_mut(location).unwrap().1 = Some(*item);
+    }
+    // Hand placed items were validated to be part of the item pool
+    for item in item_plando.values() {
+        if let Some(index) = item_pool.iter().position(|x| x == item) {
+            item_pool.remove(index);
         }
-    } else if force_vanilla_turbotrack {
-        item_placement
-            .get_mut(&ItemLocation {
-                levelid: LevelID::TurboTrack,
-                racetype: RaceType::RelicRacePlatinum,
-            })
-            .unwrap()
-            .1 = Some(RaceReward::PlatinumRelic);
     }
 
-    if force_vanilla_turbotrack {
-        item_placement
-            .get_mut(&ItemLocation {
-                levelid: LevelID::TurboTrack,
-                racetype: RaceType::RelicRaceSapphire,
-            })
-            .unwrap()
-            .1 = Some(RaceReward::SapphireRelic);
-        item_placement
-            .get_mut(&ItemLocation {
-                levelid: LevelID::TurboTrack,
-                racetype: RaceType::RelicRaceGold,
-            })
-            .unwrap()
-            .1 = Some(RaceReward::GoldRelic);
-    }
+    item_pool.shuffle(seed);
 
-    if !reward_shuffle.include_gems {
-        item_placement
-            .get_mut(&ItemLocation{levelid: LevelID::CupRed, racetype: RaceType::GemCup})
-            .unwrap()
-            .1 = Some(RaceReward::RedGem);
-        item_placement
-            .get_mut(&ItemLocation{levelid: LevelID::CupGreen, racetype: RaceType::GemCup})
-            .unwrap()
-            .1 = Some(RaceReward::GreenGem);
-        item_placement
-            .get_mut(&ItemLocation{levelid: LevelID::CupBlue, racetype: RaceType::GemCup})
-            .unwrap()
-            .1 = Some(RaceReward::BlueGem);
-        item_placement
-            .get_mut(&ItemLocation{levelid: LevelID::CupYellow, racetype: RaceType::GemCup})
-            .unwrap()
-            .1 = Some(RaceReward::YellowGem);
-        item_placement
-            .get_mut(&ItemLocation{levelid: LevelID::CupPurple, racetype: RaceType::GemCup})
-            .unwrap()
-            .1 = Some(RaceReward::PurpleGem);
+    // Fill excluded locations first, preferably with filler, otherwise with
+    // items the player has more of than any requirement asks for. Whatever
+    // ends up there is ignored by the logic from here on out
+    let mut all_requirements: Vec<UnlockRequirement> = item_placement
+        .values()
+        .flat_map(|(requirements, _)| requirements.clone())
+        .filter(|x| matches!(x, UnlockRequirement::Item(_)))
+        .collect();
+    if let Some(x) = chosen_settings.general.goal.get_required_items() {
+        all_requirements.push(UnlockRequirement::Item(x));
     }
-
-    if !reward_shuffle.include_keys {
-        for level_id in [
-            LevelID::RoosTubes,
-            LevelID::PapusPyramid,
-            LevelID::DragonMines,
-            LevelID::HotAirSkyway,
-        ] {
-            item_placement
-                .get_mut(&ItemLocation{levelid: level_id, racetype: RaceType::BossRace})
-                .unwrap()
-                .1 = Some(RaceReward::Key);
+    let mut empty_excluded_locations: Vec<ItemLocation> = item_placement
+        .iter()
+        .filter(|(location, (_, placed_item))| {
+            placed_item.is_none() && chosen_settings.is_excluded_location(location)
+        })
+        .map(|(location, _)| *location)
+        .collect();
+    empty_excluded_locations.sort();
+    // Items the player keeps no matter what: the starting inventory and the
+    // fixed items at locations the logic still counts on
+    let kept_items: Vec<RaceReward> = item_placement
+        .iter()
+        .filter(|(location, _)| !chosen_settings.is_excluded_location(location))
+        .filter_map(|(_, (_, placed_item))| *placed_item)
+        .chain(starting_items.iter().copied())
+        .collect();
+    for location in empty_excluded_locations {
+        if item_pool.is_empty() {
+            break;
         }
+        let surplus_item_index = item_pool
+            .iter()
+            .position(RaceReward::is_filler)
+            .or_else(|| get_surplus_item_index(chosen_settings, &kept_items, &item_pool, &all_requirements));
+        let Some(item_index) = surplus_item_index else {
+            return Err("Item placement failed.".to_string());
+        };
+        item_placement.get_mut(&location).unwrap().1 = Some(item_pool.remove(item_index));
+    }
+    if chosen_settings.randomization.shuffle_unlockables {
+        add_unlockables(seed, &mut item_pool);
     }
-
-    item_pool.shuffle(seed);
     // Guarantee keys are placed first, and trophies if vanilla warppad reqs
     if !shuffled_warppad_requirements {
         item_pool.sort_by_key(|k| matches!(k, RaceReward::Trophy));
     }
     item_pool.sort_by_key(|k| matches!(k, RaceReward::Key));
+    // Filler goes last, it never opens up any new locations
+    item_pool.sort_by_key(|k| !k.is_filler());
 
     let num_items_to_place = item_pool.len();
     let mut num_placed_items = 0;
@@ -635,54 +552,21 @@ fn get_item_placement(
         let item_to_place = item_pool.pop().expect("checked by while");
         //println!("{item_to_place:?}");
 
-        // Initialize player inventory with all items yet to be placed, except
-        // for the one item we want to place right now
-        let mut inventory = PlayerInventory::new();
-        for item in &item_pool {
+        // Initialize player inventory with the starting items and all items
+        // yet to be placed, except for the one item we want to place right now
+        let mut inventory = PlayerInventory::with_tricks(chosen_settings.tricks.get_enabled_tricks());
+        for item in starting_items.iter().chain(&item_pool) {
             inventory.add_item(*item);
         }
 
         // Find all locations that are reachable with the player's current
         // inventory combined with all currently reachable items
-        let mut reachable_locations: HashMap<ItemLocation, Option<RaceReward>> =
-            HashMap::new();
-        let mut reachable_empty_locations: Vec<ItemLocation> = Vec::new();
-        let mut found_placed_item = true;
-
-        while found_placed_item {
-            found_placed_item = false;
-            for (location, (requirements, placed_item)) in &item_placement {
-                if inventory.does_pass_requirements(requirements)
-                    && !reachable_locations
-                        .keys()
-                        .collect::<Vec<_>>()
-                        .contains(&location)
-                {
-                    if let Some(x) = placed_item {
-                        if !matches!(*x, RaceReward::PlatinumRelic)
-                            || reward_shuffle.include_platinum_relics
-                        {
-                            // Found a plat relic, but we didn't shuffle them:
-                            // Assume the player does not want to do plat relics
-                            inventory.add_item(*x);
-                        }
-                        found_placed_item = true;
-                    } else {
-                        reachable_empty_locations.push(*location);
-                    }
-                    // If first possible race of this warp pad:
-                    // Mark level as cleared for the purpose of boss garages
-                    if [RaceType::TrophyRace, RaceType::GemCup].contains(&location.racetype)
-                        || ([LevelID::TurboTrack, LevelID::SlideColiseum, LevelID::SkullRock, LevelID::RampageRuins, LevelID::RockyRoad, LevelID::NitroCourt].contains(&location.levelid)
-                            && [RaceType::RelicRaceSapphire, RaceType::CtrOrCrystalChallenge].contains(&location.racetype))
-                    {
-                        inventory.add_track(location.levelid);
-                        found_placed_item = true;
-                    }
-                    reachable_locations.insert(*location, *placed_item);
-                }
-            }
-        }
+        let reachable_locations = get_reachable_locations(&mut inventory, &item_placement, chosen_settings);
+        let reachable_empty_locations: Vec<ItemLocation> = reachable_locations
+            .into_iter()
+            .filter(|(_, placed_item)| placed_item.is_none())
+            .map(|(location, _)| location)
+            .collect();
         if reachable_empty_locations.is_empty() {
             print!("{num_placed_items} of {num_items_to_place} placed before abort - ");
             println!("reachable_empty_locations.is_empty()");
@@ -697,7 +581,6 @@ fn get_item_placement(
         let mut reachable_empty_locations_clone = reachable_empty_locations.clone();
         reachable_empty_locations_clone.sort();
         let chosen_location = reachable_empty_locations_clone.choose(seed).unwrap();
-        println!("{chosen_location:?}: {item_to_place}");
         item_placement.get_mut(chosen_location).unwrap().1 = Some(item_to_place);
 
         num_placed_items += 1;
@@ -719,6 +602,20 @@ fn get_item_placement(
             panic!()
         }
 
+        // Every item is placed, so make sure the goal can actually be reached
+        if !is_beatable(&item_placement, chosen_settings) {
+            return Err("Item placement failed.".to_string());
+        }
+
+        if chosen_settings.randomization.gentle_difficulty_curve {
+            let locked_locations: HashSet<ItemLocation> = preplaced_items
+                .keys()
+                .chain(item_plando.keys())
+                .copied()
+                .collect();
+            soften_difficulty_curve(seed, &mut item_placement, &locked_locations, chosen_settings);
+        }
+
         // Throw out the requirements; we no longer need them
         let mut filtered_item_placement = HashMap::new();
 
@@ -731,3 +628,200 @@ fn get_item_placement(
         Err("Item placement failed.".to_string())
     }
 }
+
+/// Check whether the chosen goal is fulfilled by the given inventory and
+/// reachable locations, as returned by `get_reachable_locations`.
+fn is_goal_reached(
+    goal: Goal,
+    inventory: &PlayerInventory,
+    reachable_locations: &HashMap<ItemLocation, Option<RaceReward>>,
+) -> bool {
+    match goal {
+        Goal::BeatOxide => reachable_locations
+            .values()
+            .any(|x| matches!(x, Some(RaceReward::BeatTheGame))),
+        Goal::CollectAllGems | Goal::PlatinumRelics(_) | Goal::TrophyHunt(_) => {
+            let required_items = goal.get_required_items().expect("goal requires items");
+            inventory.does_pass_requirements(&vec![UnlockRequirement::Item(required_items)])
+        }
+        Goal::BeatHubBosses => [
+            LevelID::RoosTubes,
+            LevelID::PapusPyramid,
+            LevelID::DragonMines,
+            LevelID::HotAirSkyway,
+        ]
+        .iter()
+        .all(|level_id| {
+            reachable_locations.contains_key(&ItemLocation {
+                levelid: *level_id,
+                racetype: RaceType::BossRace,
+            })
+        }),
+    }
+}
+
+/// Collect all locations reachable with the given inventory, repeatedly adding
+/// the items found along the way to the inventory.
+fn get_reachable_locations(
+    inventory: &mut PlayerInventory,
+    item_placement: &HashMap<ItemLocation, (Vec<UnlockRequirement>, Option<RaceReward>)>,
+    chosen_settings: &SeedSettings,
+) -> HashMap<ItemLocation, Option<RaceReward>> {
+    let mut reachable_locations: HashMap<ItemLocation, Option<RaceReward>> =
+        HashMap::new();
+    let mut found_placed_item = true;
+
+    while found_placed_item {
+        found_placed_item = false;
+        for (location, (requirements, placed_item)) in item_placement {
+            if inventory.does_pass_requirements(requirements)
+                && !reachable_locations.contains_key(location)
+            {
+                // Excluded races still get raced, but the logic never
+                // depends on the items they hold
+                if is_first_race_of_warppad(location) {
+                    inventory.add_track(location.levelid);
+                    found_placed_item = true;
+                }
+                if let Some(x) = placed_item.filter(|_| !chosen_settings.is_excluded_location(location)) {
+                    inventory.add_item(x);
+                    found_placed_item = true;
+                }
+                reachable_locations.insert(*location, *placed_item);
+            }
+        }
+    }
+
+    reachable_locations
+}
+
+/// Whether this is the first possible race of its warp pad, which marks the
+/// level as cleared for the purpose of boss garages
+fn is_first_race_of_warppad(location: &ItemLocation) -> bool {
+    [RaceType::TrophyRace, RaceType::GemCup].contains(&location.racetype)
+        || ([LevelID::TurboTrack, LevelID::SlideColiseum, LevelID::SkullRock, LevelID::RampageRuins, LevelID::RockyRoad, LevelID::NitroCourt].contains(&location.levelid)
+            && [RaceType::RelicRaceSapphire, RaceType::CtrOrCrystalChallenge].contains(&location.racetype))
+}
+
+/// All locations that become reachable at the same step of a playthrough
+pub struct Sphere {
+    pub locations: Vec<ItemLocation>,
+    /// Trophies the player holds when entering this sphere
+    pub num_trophies: u8,
+}
+
+/// Walk the playthrough step by step, starting out with the starting items.
+/// Every sphere holds the locations reachable with the items of all
+/// previous spheres, so its items only count from the next sphere on.
+pub fn get_spheres(
+    item_placement: &HashMap<ItemLocation, (Vec<UnlockRequirement>, Option<RaceReward>)>,
+    chosen_settings: &SeedSettings,
+) -> Vec<Sphere> {
+    let mut inventory = PlayerInventory::with_tricks(chosen_settings.tricks.get_enabled_tricks());
+    for item in chosen_settings.general.starting_inventory.get_items() {
+        inventory.add_item(item);
+    }
+    let mut visited_locations: HashSet<ItemLocation> = HashSet::new();
+    let mut spheres = Vec::new();
+
+    loop {
+        let mut locations: Vec<ItemLocation> = item_placement
+            .iter()
+            .filter(|(location, (requirements, _))| {
+                !visited_locations.contains(location) && inventory.does_pass_requirements(requirements)
+            })
+            .map(|(location, _)| *location)
+            .collect();
+        if locations.is_empty() {
+            break;
+        }
+        locations.sort();
+
+        let num_trophies = inventory.get_items()[&RaceReward::Trophy];
+        for location in &locations {
+            visited_locations.insert(*location);
+            // Excluded races still get raced, but the logic never depends on
+            // the items they hold
+            if is_first_race_of_warppad(location) {
+                inventory.add_track(location.levelid);
+            }
+            if let Some(x) = item_placement[location].1.filter(|_| !chosen_settings.is_excluded_location(location)) {
+                inventory.add_item(x);
+            }
+        }
+
+        spheres.push(Sphere { locations, num_trophies });
+    }
+
+    spheres
+}
+
+/// Move trophies out of the first third of the playthrough by swapping them
+/// with items of later spheres, as long as the goal stays reachable. As the
+/// bots get faster with every trophy, this keeps the early races easier.
+fn soften_difficulty_curve(
+    seed: &mut ChaCha8Rng,
+    item_placement: &mut HashMap<ItemLocation, (Vec<UnlockRequirement>, Option<RaceReward>)>,
+    locked_locations: &HashSet<ItemLocation>,
+    chosen_settings: &SeedSettings,
+) {
+    let spheres = get_spheres(item_placement, chosen_settings);
+    let num_early_spheres = spheres.len().div_ceil(3);
+    let is_movable = |location: &ItemLocation| {
+        !locked_locations.contains(location) && !chosen_settings.is_excluded_location(location)
+    };
+
+    let early_trophy_locations: Vec<ItemLocation> = spheres[..num_early_spheres]
+        .iter()
+        .flat_map(|x| x.locations.clone())
+        .filter(|x| is_movable(x) && matches!(item_placement[x].1, Some(RaceReward::Trophy)))
+        .collect();
+    let mut late_locations: Vec<ItemLocation> = spheres[num_early_spheres..]
+        .iter()
+        .flat_map(|x| x.locations.clone())
+        .filter(|x| {
+            is_movable(x)
+                && !matches!(item_placement[x].1, Some(RaceReward::Trophy | RaceReward::BeatTheGame))
+        })
+        .collect();
+    late_locations.shuffle(seed);
+
+    for trophy_location in early_trophy_locations {
+        let swap_index = (0..late_locations.len()).find(|i| {
+            swap_items(item_placement, &trophy_location, &late_locations[*i]);
+            if is_beatable(item_placement, chosen_settings) {
+                true
+            } else {
+                swap_items(item_placement, &trophy_location, &late_locations[*i]);
+                false
+            }
+        });
+        if let Some(i) = swap_index {
+            late_locations.remove(i);
+        }
+    }
+}
+
+fn swap_items(
+    item_placement: &mut HashMap<ItemLocation, (Vec<UnlockRequirement>, Option<RaceReward>)>,
+    location_a: &ItemLocation,
+    location_b: &ItemLocation,
+) {
+    let item_a = item_placement[location_a].1;
+    let item_b = std::mem::replace(&mut item_placement.get_mut(location_b).unwrap().1, item_a);
+    item_placement.get_mut(location_a).unwrap().1 = item_b;
+}
+
+/// Check whether the goal can be reached when starting out with only the
+/// starting items
+pub fn is_beatable(
+    item_placement: &HashMap<ItemLocation, (Vec<UnlockRequirement>, Option<RaceReward>)>,
+    chosen_settings: &SeedSettings,
+) -> bool {
+    let mut inventory = PlayerInventory::with_tricks(chosen_settings.tricks.get_enabled_tricks());
+    for item in chosen_settings.general.starting_inventory.get_items() {
+        inventory.add_item(item);
+    }
+    let reachable_locations = get_reachable_locations(&mut inventory, item_placement, chosen_settings);
+    is_goal_reached(chosen_settings.general.goal, &inventory, &reachable_locations)
+}
@@ -0,0 +1,231 @@
+use std::path::Path;
+
+use crate::seed_generation::{
+    game_world::get_vanilla_gameworld,
+    gem_cups::GEM_CUPS,
+    item_randomization::player_inventory::PlayerInventory,
+    randomization_datastructures::{RaceReward, UNLOCKABLES},
+    tricks::Trick,
+    version::check_db_version,
+    write_rando_db::{get_db_version, read_database, DB_START_MARKER},
+};
+
+const CARD_SIZE: usize = 0x20000;
+const BLOCK_SIZE: usize = 0x2000;
+const FRAME_SIZE: usize = 0x80;
+
+/// Headers some emulators and tools put in front of the raw card data
+const CARD_HEADERS: [(&[u8], usize); 3] = [
+    // DexDrive (.gme)
+    (b"123-456-STD", 0xF40),
+    // Connectix Virtual Game Station (.mem, .vgs)
+    (b"VgsM", 0x40),
+    // PSP / PS3 virtual memory card (.vmp)
+    (b"\0PMV", 0x80),
+];
+
+/// Save file names of Crash Team Racing, by region
+const CTR_SAVE_NAMES: [&str; 3] = ["BASCUS-94426", "BESCES-01420", "BISCPS-10118"];
+
+/// Directory frame state of the first block of a save file
+const FIRST_BLOCK_IN_USE: u8 = 0x51;
+
+/// The randomizer keeps a copy of its database in save slots 2 to 4, the same
+/// way it is kept in RAM, followed by its progress data: item counts, gems,
+/// unlockables, beaten tracks and gem cups and the Oxide races won. See
+/// "Progress data" in docs/RandoDatabase.md for the layout.
+pub const RANDO_DATA_SIZE: usize = 19;
+
+/// Adventure progress read from a memory card
+#[derive(Debug)]
+pub struct SaveProgress {
+    pub inventory: PlayerInventory,
+    /// 1 after beating Oxide's first race, 2 after his final challenge
+    pub oxide_races_won: u8,
+}
+
+impl SaveProgress {
+    /// Adventure completion as shown on the save file, see the handbook
+    pub fn get_completion_percentage(&self) -> u8 {
+        let items = self.inventory.get_items();
+        let count = |x: &[RaceReward]| -> u8 { x.iter().map(|item| items[item]).sum() };
+
+        let relics = [
+            items[&RaceReward::SapphireRelic],
+            items[&RaceReward::GoldRelic],
+            items[&RaceReward::PlatinumRelic],
+        ];
+        let gold_and_platinum = u16::from(relics[1]) + u16::from(relics[2]);
+
+        2 * count(&[RaceReward::Trophy])
+            + count(&[
+                RaceReward::RedCtrToken,
+                RaceReward::GreenCtrToken,
+                RaceReward::BlueCtrToken,
+                RaceReward::YellowCtrToken,
+                RaceReward::PurpleCtrToken,
+            ])
+            + count(&[RaceReward::Key])
+            + count(&[
+                RaceReward::RedGem,
+                RaceReward::GreenGem,
+                RaceReward::BlueGem,
+                RaceReward::YellowGem,
+                RaceReward::PurpleGem,
+            ])
+            + 2 * relics.into_iter().max().unwrap_or_default()
+            + u8::from(gold_and_platinum >= 36)
+            + match self.oxide_races_won {
+                0 => 0,
+                1 => 2,
+                _ => 3,
+            }
+    }
+}
+
+pub fn read_memory_card(path: &Path, tricks: Vec<Trick>) -> Result<SaveProgress, String> {
+    let bytes = std::fs::read(path).map_err(|_| "Could not read the memory card file!".to_owned())?;
+    parse_memory_card(&bytes, tricks)
+}
+
+/// Find the randomizer save of a memory card image and decode its progress.
+/// Accepts raw cards (.mcr, .mcd, .srm), cards with an emulator header, and
+/// single exported saves (.mcs).
+pub fn parse_memory_card(bytes: &[u8], tricks: Vec<Trick>) -> Result<SaveProgress, String> {
+    let save_file = get_ctr_save_file(bytes)?;
+
+    let (database, rando_data) = save_file
+        .windows(DB_START_MARKER.len())
+        .position(|x| x == DB_START_MARKER)
+        .and_then(|x| read_database(&save_file[x..]).map(|(database, size)| (database, &save_file[x + size..])))
+        .filter(|(_, x)| x.len() >= RANDO_DATA_SIZE)
+        .ok_or("The Crash Team Racing save holds no randomizer data!".to_owned())?;
+    check_db_version(get_db_version(&database))?;
+
+    Ok(decode_rando_data(rando_data, tricks))
+}
+
+fn get_ctr_save_file(bytes: &[u8]) -> Result<Vec<u8>, String> {
+    let is_ctr_save = |frame: &[u8]| -> bool {
+        let name: String = frame[0x0A..0x1E]
+            .iter()
+            .take_while(|x| **x != 0)
+            .map(|x| char::from(*x))
+            .collect();
+        frame[0] == FIRST_BLOCK_IN_USE && CTR_SAVE_NAMES.iter().any(|x| name.starts_with(x))
+    };
+
+    // Single exported save: one directory frame followed by the save blocks.
+    // Card images with a header can have the same size, e.g. .vmp cards.
+    let has_card_header = CARD_HEADERS.iter().any(|(magic, _)| bytes.starts_with(magic));
+    if !has_card_header && bytes.len() > FRAME_SIZE && (bytes.len() - FRAME_SIZE).is_multiple_of(BLOCK_SIZE) {
+        return if is_ctr_save(&bytes[..FRAME_SIZE]) {
+            Ok(bytes[FRAME_SIZE..].to_vec())
+        } else {
+            Err("The save file is not a Crash Team Racing save!".to_owned())
+        };
+    }
+
+    let card = get_raw_card(bytes)?;
+
+    // Block 0 holds the directory, with one frame for each of the 15 save
+    // blocks. A save spanning several blocks links them by index.
+    let first_block = (1..16)
+        .find(|x| is_ctr_save(&card[x * FRAME_SIZE..(x + 1) * FRAME_SIZE]))
+        .ok_or("The memory card holds no Crash Team Racing save!".to_owned())?;
+
+    let mut save_file = Vec::new();
+    let mut block = first_block;
+    for _ in 1..16 {
+        save_file.extend_from_slice(&card[block * BLOCK_SIZE..(block + 1) * BLOCK_SIZE]);
+
+        let frame = &card[block * FRAME_SIZE..(block + 1) * FRAME_SIZE];
+        let next_block = usize::from(u16::from_le_bytes([frame[8], frame[9]]));
+        if !(0..15).contains(&next_block) {
+            break;
+        }
+        block = next_block + 1;
+    }
+
+    Ok(save_file)
+}
+
+fn get_raw_card(bytes: &[u8]) -> Result<&[u8], String> {
+    let card = CARD_HEADERS
+        .iter()
+        .find(|(magic, _)| bytes.starts_with(magic))
+        .map_or(bytes, |(_, header_size)| &bytes[*header_size.min(&bytes.len())..]);
+
+    if card.len() != CARD_SIZE || !card.starts_with(b"MC") {
+        return Err("The file is not a PS1 memory card image!".to_owned());
+    }
+
+    Ok(card)
+}
+
+/// Decode the randomizer data following the marker, which needs to be at
+/// least RANDO_DATA_SIZE bytes long
+pub fn decode_rando_data(data: &[u8], tricks: Vec<Trick>) -> SaveProgress {
+    let mut inventory = PlayerInventory::with_tricks(tricks);
+
+    let counted_items = [
+        RaceReward::Trophy,
+        RaceReward::RedCtrToken,
+        RaceReward::GreenCtrToken,
+        RaceReward::BlueCtrToken,
+        RaceReward::YellowCtrToken,
+        RaceReward::PurpleCtrToken,
+        RaceReward::SapphireRelic,
+        RaceReward::GoldRelic,
+        RaceReward::PlatinumRelic,
+        RaceReward::Key,
+    ];
+    for (item, count) in counted_items.into_iter().zip(data) {
+        for _ in 0..*count {
+            inventory.add_item(item);
+        }
+    }
+
+    let gems = [
+        RaceReward::RedGem,
+        RaceReward::GreenGem,
+        RaceReward::BlueGem,
+        RaceReward::YellowGem,
+        RaceReward::PurpleGem,
+    ];
+    for (index, gem) in gems.into_iter().enumerate() {
+        if data[10] & (1 << index) != 0 {
+            inventory.add_item(gem);
+        }
+    }
+
+    let unlocks = u16::from_le_bytes([data[11], data[12]]);
+    for (index, unlockable) in UNLOCKABLES.into_iter().enumerate() {
+        if unlocks & (1 << index) != 0 {
+            inventory.add_item(unlockable);
+        }
+    }
+
+    let beaten_tracks = u32::from_le_bytes([data[13], data[14], data[15], data[16]]);
+    let mut tracks: Vec<_> = get_vanilla_gameworld()
+        .get_warppad_links()
+        .into_keys()
+        .filter(|x| !GEM_CUPS.contains(x))
+        .collect();
+    tracks.sort();
+    for track in tracks {
+        if beaten_tracks & (1 << track as u32) != 0 {
+            inventory.add_track(track);
+        }
+    }
+    for (index, cup) in GEM_CUPS.into_iter().enumerate() {
+        if data[17] & (1 << index) != 0 {
+            inventory.add_track(cup);
+        }
+    }
+
+    SaveProgress {
+        inventory,
+        oxide_races_won: data[18],
+    }
+}
@@ -1,10 +1,26 @@
+pub mod batch_statistics;
+pub mod difficulty_curve;
+pub mod hint_sheet;
+pub mod hints;
+pub mod html_spoilerlog;
+pub mod http_server;
 pub mod item_randomization;
+pub mod memory_card;
+pub mod output_paths;
+pub mod ram_snapshot;
 pub mod randomization_datastructures;
 pub mod randomize_game;
 pub mod randomize_warppad_requirements;
+pub mod relic_times;
 pub mod rom_patching;
+pub mod seed_estimate;
 pub mod seed_gen_main;
+pub mod sealed_spoilerlog;
 pub mod seed_settings;
+pub mod tracker;
+pub mod tricks;
+pub mod version;
 pub mod write_rando_db;
 pub mod game_world;
+pub mod gem_cups;
 pub mod spoilerlog;
@@ -0,0 +1,156 @@
+use std::path::{Path, PathBuf};
+
+use crate::seed_generation::spoilerlog::get_seed_hash;
+
+pub const DEFAULT_FILENAME_TEMPLATE: &str = "CTR-Randomizer_{seed}";
+
+/// What to do if a file of the seed already exists
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CollisionHandling {
+    /// Keep the existing files and don't write the seed
+    Skip = 0,
+    Overwrite = 1,
+    /// Add _2, _3, ... to the file name until it is free
+    Suffix = 2,
+}
+
+impl std::fmt::Display for CollisionHandling {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            CollisionHandling::Skip => String::from("Skip"),
+            CollisionHandling::Overwrite => String::from("Overwrite"),
+            CollisionHandling::Suffix => String::from("Suffix"),
+        })
+    }
+}
+
+impl TryFrom<i32> for CollisionHandling {
+    type Error = ();
+
+    fn try_from(v: i32) -> Result<Self, Self::Error> {
+        match v {
+            x if x == CollisionHandling::Skip as i32 => Ok(CollisionHandling::Skip),
+            x if x == CollisionHandling::Overwrite as i32 => Ok(CollisionHandling::Overwrite),
+            x if x == CollisionHandling::Suffix as i32 => Ok(CollisionHandling::Suffix),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Where the files of a seed are written. Not part of the seed settings, as
+/// they don't change the seed.
+#[derive(Debug, Clone)]
+pub struct OutputSettings {
+    /// None writes next to the vanilla ROM
+    pub directory: Option<PathBuf>,
+    /// File name without extension, with the placeholders {seed}, {hash} and
+    /// {preset}
+    pub filename_template: String,
+    pub preset_name: String,
+    pub collision_handling: CollisionHandling,
+    /// Write all files of a seed into a folder of the same name
+    pub seed_subfolder: bool,
+}
+
+impl Default for OutputSettings {
+    fn default() -> Self {
+        OutputSettings {
+            directory: None,
+            filename_template: DEFAULT_FILENAME_TEMPLATE.to_owned(),
+            preset_name: String::new(),
+            collision_handling: CollisionHandling::Suffix,
+            seed_subfolder: false,
+        }
+    }
+}
+
+impl OutputSettings {
+    /// Path of the new ROM, which the patch file, spoiler log and hint sheet
+    /// are named after. Creates the output folder if needed.
+    pub fn get_new_rom_path(&self, old_rom_path: &Path, seed: u32) -> Result<PathBuf, String> {
+        self.get_free_rom_path(old_rom_path, &self.get_file_stem(seed)?)
+    }
+
+    /// Path of a ROM patched from a shared patch file whose seed is unknown,
+    /// named after the patch file instead of the file name template
+    pub fn get_patched_rom_path(&self, old_rom_path: &Path, patch_file_path: &Path) -> Result<PathBuf, String> {
+        let file_stem = patch_file_path
+            .file_stem()
+            .map(|x| x.to_string_lossy().to_string())
+            .ok_or("The patch file has no file name!".to_owned())?;
+        self.get_free_rom_path(old_rom_path, &file_stem)
+    }
+
+    /// Path of the new ROM in the output folder, handling files of the same
+    /// name as the collision handling says
+    fn get_free_rom_path(&self, old_rom_path: &Path, file_stem: &str) -> Result<PathBuf, String> {
+        let mut directory = match &self.directory {
+            Some(x) => x.clone(),
+            None => old_rom_path.parent().map(Path::to_path_buf).unwrap_or_default(),
+        };
+        if self.seed_subfolder {
+            directory.push(file_stem);
+        }
+
+        let mut new_file_stem = file_stem.to_owned();
+        if get_seed_files(&directory, &new_file_stem).iter().any(|x| x.exists()) {
+            match self.collision_handling {
+                CollisionHandling::Skip => {
+                    return Err(format!(
+                        "Files for '{file_stem}' already exist in\n{}!\nSkipped writing the seed.",
+                        directory.display(),
+                    ));
+                }
+                // Files of the old seed that the new seed doesn't write
+                // would otherwise be mistaken for the new seed's
+                CollisionHandling::Overwrite => {
+                    for seed_file in get_seed_files(&directory, &new_file_stem) {
+                        if seed_file.exists() && seed_file != old_rom_path {
+                            std::fs::remove_file(&seed_file)
+                                .map_err(|_| format!("Could not remove the old file\n{}!", seed_file.display()))?;
+                        }
+                    }
+                }
+                CollisionHandling::Suffix => {
+                    new_file_stem = (2..)
+                        .map(|x| format!("{file_stem}_{x}"))
+                        .find(|x| !get_seed_files(&directory, x).iter().any(|x| x.exists()))
+                        .expect("some suffix is free");
+                }
+            }
+        }
+
+        std::fs::create_dir_all(&directory)
+            .map_err(|_| format!("Could not create the output folder\n{}!", directory.display()))?;
+
+        Ok(directory.join(format!("{new_file_stem}.bin")))
+    }
+
+    /// Fill in the file name template, replacing characters file names cannot
+    /// hold
+    fn get_file_stem(&self, seed: u32) -> Result<String, String> {
+        let file_stem: String = self
+            .filename_template
+            .replace("{seed}", &seed.to_string())
+            .replace("{hash}", &get_seed_hash(seed).replace(", ", "-"))
+            .replace("{preset}", self.preset_name.trim())
+            .chars()
+            .map(|x| if "/\\:*?\"<>|".contains(x) || x.is_control() { '_' } else { x })
+            .collect();
+
+        let file_stem = file_stem.trim().trim_end_matches('.');
+        if file_stem.is_empty() {
+            return Err("The file name template gives an empty file name!".to_owned());
+        }
+
+        Ok(file_stem.to_owned())
+    }
+}
+
+/// Every file seed generation may write for a file stem
+fn get_seed_files(directory: &Path, file_stem: &str) -> Vec<PathBuf> {
+    [".bin", ".bsdiff4", ".json", "_spoilers.json", "_spoilers.html", "_spoilers.sealed", "_hintsheet.md", "_hintsheet.json"]
+        .iter()
+        .map(|x| directory.join(format!("{file_stem}{x}")))
+        .collect()
+}
@@ -0,0 +1,140 @@
+use std::{collections::BTreeMap, path::Path};
+
+use crate::seed_generation::{
+    memory_card::{decode_rando_data, SaveProgress, RANDO_DATA_SIZE},
+    tricks::Trick,
+    version::check_db_version,
+    write_rando_db::{get_db_seed, get_db_version, read_database, DB_START_MARKER},
+};
+
+/// Size of the PS1 main RAM, as written by emulators' memory snapshots
+const RAM_SIZE: usize = 0x200000;
+
+/// Randomizer state read from a RAM dump of a running game
+#[derive(Debug)]
+pub struct RamSnapshot {
+    /// Database of the seed being played, as written by write_db_to_rom
+    pub database: BTreeMap<u32, u16>,
+    pub progress: SaveProgress,
+}
+
+impl RamSnapshot {
+    /// Seed of the running game, None if the seed hash is not shown
+    pub fn get_seed(&self) -> Option<u32> {
+        get_db_seed(&self.database)
+    }
+}
+
+pub fn read_ram_snapshot(path: &Path, tricks: Vec<Trick>) -> Result<RamSnapshot, String> {
+    let bytes = std::fs::read(path).map_err(|_| "Could not read the RAM snapshot file!".to_owned())?;
+    parse_ram_snapshot(&bytes, tricks)
+}
+
+/// Find the randomizer database in a RAM dump and decode the live adventure
+/// progress the base mod keeps right behind it. Accepts plain 2 MB memory
+/// snapshots and uncompressed save states, which hold the RAM somewhere
+/// inside.
+pub fn parse_ram_snapshot(bytes: &[u8], tricks: Vec<Trick>) -> Result<RamSnapshot, String> {
+    // gzip and zstd, as used by the save states of most emulators
+    if bytes.starts_with(&[0x1F, 0x8B]) || bytes.starts_with(&[0x28, 0xB5, 0x2F, 0xFD]) {
+        return Err("Compressed save states are not supported,\nuse an uncompressed save state or a RAM snapshot!".to_owned());
+    }
+    if bytes.len() < RAM_SIZE {
+        return Err("The file is too small to hold the PS1 RAM!".to_owned());
+    }
+
+    // The marker bytes could also show up by chance, so only accept a
+    // database that is properly terminated
+    let (database, progress) = bytes
+        .windows(DB_START_MARKER.len())
+        .enumerate()
+        .filter(|(_, x)| *x == DB_START_MARKER)
+        .find_map(|(start, _)| {
+            let (database, size) = read_database(&bytes[start..])?;
+            let progress = bytes.get(start + size..start + size + RANDO_DATA_SIZE)?;
+            Some((database, progress))
+        })
+        .ok_or("The RAM snapshot holds no randomizer data!\nIs a randomized game running?".to_owned())?;
+    check_db_version(get_db_version(&database))?;
+
+    Ok(RamSnapshot {
+        database,
+        progress: decode_rando_data(progress, tricks),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::seed_generation::randomization_datastructures::{LevelID, RaceReward};
+
+    /// Database of seed 0x12345678 followed by its progress data
+    const RAM_DATABASE: &[u8] = include_bytes!("../../res/test/ram_database.bin");
+    /// The same with database version 2
+    const RAM_DATABASE_DB_VERSION_2: &[u8] = include_bytes!("../../res/test/ram_database_db_version_2.bin");
+    /// A start marker that is never followed by an end marker
+    const RAM_STRAY_MARKER: &[u8] = include_bytes!("../../res/test/ram_stray_marker.bin");
+
+    /// 2 MB of RAM holding the given parts at the given offsets
+    fn get_ram(parts: &[(usize, &[u8])]) -> Vec<u8> {
+        let mut ram = vec![0; RAM_SIZE];
+        for (offset, part) in parts {
+            ram[*offset..offset + part.len()].copy_from_slice(part);
+        }
+        ram
+    }
+
+    #[test]
+    fn reads_database_and_progress() {
+        let snapshot = parse_ram_snapshot(&get_ram(&[(0x8_0000, RAM_DATABASE)]), Vec::new()).unwrap();
+
+        assert_eq!(snapshot.get_seed(), Some(0x1234_5678));
+        let items = snapshot.progress.inventory.get_items();
+        assert_eq!(items[&RaceReward::Trophy], 4);
+        assert_eq!(items[&RaceReward::PurpleCtrToken], 2);
+        assert_eq!(items[&RaceReward::GoldRelic], 5);
+        assert_eq!(items[&RaceReward::Key], 2);
+        assert_eq!(items[&RaceReward::RedGem], 1);
+        assert_eq!(items[&RaceReward::PurpleGem], 1);
+        assert_eq!(items[&RaceReward::PapuPapuUnlock], 1);
+        let mut tracks = snapshot.progress.inventory.get_beaten_tracks().to_vec();
+        tracks.sort();
+        assert_eq!(tracks, vec![LevelID::DingoCanyon, LevelID::DragonMines, LevelID::CupRed]);
+        assert_eq!(snapshot.progress.oxide_races_won, 1);
+    }
+
+    #[test]
+    fn skips_stray_marker() {
+        let ram = get_ram(&[(0x1000, RAM_STRAY_MARKER)]);
+        assert!(parse_ram_snapshot(&ram, Vec::new()).is_err());
+
+        let ram = get_ram(&[(0x1000, RAM_STRAY_MARKER), (0x8_0000, RAM_DATABASE)]);
+        assert_eq!(parse_ram_snapshot(&ram, Vec::new()).unwrap().get_seed(), Some(0x1234_5678));
+    }
+
+    #[test]
+    fn rejects_compressed_save_states() {
+        for save_state in [
+            &include_bytes!("../../res/test/savestate.gz")[..],
+            &include_bytes!("../../res/test/savestate.zst")[..],
+        ] {
+            let mut bytes = save_state.to_vec();
+            bytes.resize(RAM_SIZE, 0);
+            let err = parse_ram_snapshot(&bytes, Vec::new()).unwrap_err();
+            assert!(err.starts_with("Compressed save states"));
+        }
+    }
+
+    #[test]
+    fn rejects_files_smaller_than_ram() {
+        let err = parse_ram_snapshot(RAM_DATABASE, Vec::new()).unwrap_err();
+        assert!(err.contains("too small"));
+    }
+
+    #[test]
+    fn rejects_other_db_version() {
+        let ram = get_ram(&[(0x8_0000, RAM_DATABASE_DB_VERSION_2)]);
+        let err = parse_ram_snapshot(&ram, Vec::new()).unwrap_err();
+        assert!(err.contains("different generator version"));
+    }
+}
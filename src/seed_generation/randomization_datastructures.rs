@@ -1,9 +1,23 @@
-use crate::seed_generation::{game_world::GameWorld, seed_settings::{BossGarageRequirements, FinalOxideUnlock, RelicTime}};
+use std::collections::BTreeMap;
+
+use crate::seed_generation::{
+    game_world::GameWorld, hints::Hint, relic_times::RelicTimes, seed_settings::{BossGarageRequirements, FinalOxideUnlock, Goal, RelicTime}, tricks::Trick
+};
 
 #[derive(Debug)]
 pub struct GameSetup {
     pub game_world: GameWorld,
     pub settings: Vec<(SettingID, SettingValue)>,
+    pub starting_inventory: Vec<(RaceReward, u8)>,
+    pub relic_times: BTreeMap<LevelID, RelicTimes>,
+    /// Item placement attempts it took to shuffle the race rewards, 0 if
+    /// they weren't shuffled
+    pub placement_attempts: u32,
+    pub hints: Vec<Hint>,
+    /// Tracks of every gem cup, empty for vanilla line-ups
+    pub gem_cup_tracks: BTreeMap<LevelID, [LevelID; 4]>,
+    /// Unlockables handed out on top of the reward of a race
+    pub bonus_unlockables: BTreeMap<ItemLocation, RaceReward>,
 }
 
 #[derive(PartialEq, PartialOrd, Eq, Hash, Copy, Clone, Ord, Debug)]
@@ -72,6 +86,46 @@ impl std::fmt::Display for LevelID {
     }
 }
 
+impl std::str::FromStr for LevelID {
+    type Err = ();
+
+    /// Parse a level by its display name, ignoring case
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        [
+            LevelID::DingoCanyon,
+            LevelID::DragonMines,
+            LevelID::BlizzardBluff,
+            LevelID::CrashCove,
+            LevelID::TigerTemple,
+            LevelID::PapusPyramid,
+            LevelID::RoosTubes,
+            LevelID::HotAirSkyway,
+            LevelID::SewerSpeedway,
+            LevelID::MysteryCaves,
+            LevelID::CortexCastle,
+            LevelID::NGinLabs,
+            LevelID::PolarPass,
+            LevelID::OxideStation,
+            LevelID::CocoPark,
+            LevelID::TinyArena,
+            LevelID::SlideColiseum,
+            LevelID::TurboTrack,
+            LevelID::NitroCourt,
+            LevelID::RampageRuins,
+            LevelID::SkullRock,
+            LevelID::RockyRoad,
+            LevelID::CupRed,
+            LevelID::CupGreen,
+            LevelID::CupBlue,
+            LevelID::CupYellow,
+            LevelID::CupPurple,
+        ]
+        .into_iter()
+        .find(|x| x.to_string().eq_ignore_ascii_case(s.trim()))
+        .ok_or(())
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Hash, Clone, PartialOrd, Ord)]
 pub enum UnlockStage {
     One,
@@ -81,7 +135,8 @@ pub enum UnlockStage {
 #[derive(Debug, Clone)]
 pub enum UnlockRequirement {
     Item(UnlockRequirementItem),
-    LevelList(Vec<LevelID>)
+    LevelList(Vec<LevelID>),
+    Trick(Trick),
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
@@ -103,6 +158,7 @@ pub enum RequiredItem {
     SapphireRelic = 97,
     GoldRelic = 353,
     PlatinumRelic = 609,
+    GoldOrPlatinumRelic = 1633,
     AnyRelic = 1889,
     Key = 99,
     RedGem = 95,
@@ -151,6 +207,7 @@ impl std::fmt::Display for RequiredItem {
             RequiredItem::SapphireRelic => String::from("Sapphire Relic"),
             RequiredItem::GoldRelic => String::from("Gold Relic"),
             RequiredItem::PlatinumRelic => String::from("Platinum Relic"),
+            RequiredItem::GoldOrPlatinumRelic => String::from("Gold or Platinum Relic"),
             RequiredItem::AnyRelic => String::from("Any Relic"),
             RequiredItem::Key => String::from("Key"),
             RequiredItem::RedGem => String::from("Red Gem"),
@@ -163,6 +220,82 @@ impl std::fmt::Display for RequiredItem {
     }
 }
 
+/// Find the item whose display name matches the given name, ignoring case
+/// and whitespace, and accepting plurals like "Trophies" or "Keys"
+fn find_item_by_name<T: std::fmt::Display>(name: &str, items: impl IntoIterator<Item = T>) -> Result<T, ()> {
+    let normalize = |x: &str| -> String {
+        x.chars()
+            .filter(|c| !c.is_whitespace())
+            .collect::<String>()
+            .to_lowercase()
+    };
+    let name = normalize(name);
+    let singular_names = [
+        name.clone(),
+        name.strip_suffix("ies").map(|x| format!("{x}y")).unwrap_or_default(),
+        name.strip_suffix('s').unwrap_or_default().to_owned(),
+    ];
+
+    items
+        .into_iter()
+        .find(|x| singular_names.contains(&normalize(&x.to_string())))
+        .ok_or(())
+}
+
+impl std::str::FromStr for RequiredItem {
+    type Err = ();
+
+    /// Parse an item type by its display name, see `find_item_by_name`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        find_item_by_name(s, [
+            RequiredItem::Trophy,
+            RequiredItem::RedCtrToken,
+            RequiredItem::GreenCtrToken,
+            RequiredItem::BlueCtrToken,
+            RequiredItem::YellowCtrToken,
+            RequiredItem::PurpleCtrToken,
+            RequiredItem::AnyCtrToken,
+            RequiredItem::SapphireRelic,
+            RequiredItem::GoldRelic,
+            RequiredItem::PlatinumRelic,
+            RequiredItem::GoldOrPlatinumRelic,
+            RequiredItem::AnyRelic,
+            RequiredItem::Key,
+            RequiredItem::RedGem,
+            RequiredItem::GreenGem,
+            RequiredItem::BlueGem,
+            RequiredItem::YellowGem,
+            RequiredItem::PurpleGem,
+            RequiredItem::AnyGem,
+        ])
+    }
+}
+
+impl RequiredItem {
+    /// Highest count of this item type a player can possibly hold at once
+    pub fn get_max_count(&self) -> u8 {
+        match self {
+            RequiredItem::Trophy => 16,
+            RequiredItem::RedCtrToken
+            | RequiredItem::GreenCtrToken
+            | RequiredItem::BlueCtrToken
+            | RequiredItem::YellowCtrToken
+            | RequiredItem::PurpleCtrToken => 4,
+            RequiredItem::AnyCtrToken => 20,
+            RequiredItem::SapphireRelic | RequiredItem::GoldRelic | RequiredItem::PlatinumRelic => 18,
+            RequiredItem::GoldOrPlatinumRelic => 36,
+            RequiredItem::AnyRelic => 54,
+            RequiredItem::Key => 4,
+            RequiredItem::RedGem
+            | RequiredItem::GreenGem
+            | RequiredItem::BlueGem
+            | RequiredItem::YellowGem
+            | RequiredItem::PurpleGem => 1,
+            RequiredItem::AnyGem => 5,
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, PartialOrd, Ord)]
 pub struct ItemLocation {
     pub levelid: LevelID,
@@ -181,6 +314,40 @@ pub enum RaceType {
     GemCup = 95,
 }
 
+impl std::fmt::Display for RaceType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            RaceType::TrophyRace => String::from("TrophyRace"),
+            RaceType::CtrOrCrystalChallenge => String::from("CtrOrCrystalChallenge"),
+            RaceType::RelicRaceSapphire => String::from("RelicRaceSapphire"),
+            RaceType::RelicRaceGold => String::from("RelicRaceGold"),
+            RaceType::RelicRacePlatinum => String::from("RelicRacePlatinum"),
+            RaceType::BossRace => String::from("BossRace"),
+            RaceType::GemCup => String::from("GemCup"),
+        })
+    }
+}
+
+impl std::str::FromStr for RaceType {
+    type Err = ();
+
+    /// Parse a race type by its display name, ignoring case
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        [
+            RaceType::TrophyRace,
+            RaceType::CtrOrCrystalChallenge,
+            RaceType::RelicRaceSapphire,
+            RaceType::RelicRaceGold,
+            RaceType::RelicRacePlatinum,
+            RaceType::BossRace,
+            RaceType::GemCup,
+        ]
+        .into_iter()
+        .find(|x| x.to_string().eq_ignore_ascii_case(s.trim()))
+        .ok_or(())
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
 #[repr(u16)]
 pub enum RaceReward {
@@ -200,6 +367,54 @@ pub enum RaceReward {
     YellowGem = 863,
     PurpleGem = 1119,
     BeatTheGame = 0,
+    /// Filler for race rewards that were moved into the starting inventory,
+    /// see docs/RandoDatabase.md for how the base mod handles it
+    Nothing = 1,
+    // Unlockables have no item id in the game, these ids are the generator's
+    // own, see docs/RandoDatabase.md
+    // Unlockable characters
+    RipperRooUnlock = 100,
+    PapuPapuUnlock = 356,
+    KomodoJoeUnlock = 612,
+    PinstripeUnlock = 868,
+    FakeCrashUnlock = 1124,
+    NTropyUnlock = 1380,
+    PentaPenguinUnlock = 1636,
+    NOxideUnlock = 1892,
+    // Unlockable arcade tracks
+    SlideColiseumUnlock = 101,
+    TurboTrackUnlock = 357,
+    // Unlockable battle arenas
+    SkullRockUnlock = 102,
+    RampageRuinsUnlock = 358,
+    RockyRoadUnlock = 614,
+    NitroCourtUnlock = 870,
+}
+
+/// Characters, arcade tracks and battle arenas that can be shuffled into the
+/// filler of the item pool. None of them are ever needed to progress.
+pub const UNLOCKABLES: [RaceReward; 14] = [
+    RaceReward::RipperRooUnlock,
+    RaceReward::PapuPapuUnlock,
+    RaceReward::KomodoJoeUnlock,
+    RaceReward::PinstripeUnlock,
+    RaceReward::FakeCrashUnlock,
+    RaceReward::NTropyUnlock,
+    RaceReward::PentaPenguinUnlock,
+    RaceReward::NOxideUnlock,
+    RaceReward::SlideColiseumUnlock,
+    RaceReward::TurboTrackUnlock,
+    RaceReward::SkullRockUnlock,
+    RaceReward::RampageRuinsUnlock,
+    RaceReward::RockyRoadUnlock,
+    RaceReward::NitroCourtUnlock,
+];
+
+impl RaceReward {
+    /// Items that never open up any new locations
+    pub fn is_filler(&self) -> bool {
+        matches!(self, RaceReward::Nothing) || UNLOCKABLES.contains(self)
+    }
 }
 
 impl std::fmt::Display for RaceReward {
@@ -221,10 +436,54 @@ impl std::fmt::Display for RaceReward {
             RaceReward::YellowGem => String::from("Yellow Gem"),
             RaceReward::PurpleGem => String::from("Purple Gem"),
             RaceReward::BeatTheGame => String::from("YOU WIN"),
+            RaceReward::Nothing => String::from("Nothing"),
+            RaceReward::RipperRooUnlock => String::from("Ripper Roo Unlock"),
+            RaceReward::PapuPapuUnlock => String::from("Papu Papu Unlock"),
+            RaceReward::KomodoJoeUnlock => String::from("Komodo Joe Unlock"),
+            RaceReward::PinstripeUnlock => String::from("Pinstripe Unlock"),
+            RaceReward::FakeCrashUnlock => String::from("Fake Crash Unlock"),
+            RaceReward::NTropyUnlock => String::from("N. Tropy Unlock"),
+            RaceReward::PentaPenguinUnlock => String::from("Penta Penguin Unlock"),
+            RaceReward::NOxideUnlock => String::from("N. Oxide Unlock"),
+            RaceReward::SlideColiseumUnlock => String::from("Slide Coliseum Unlock"),
+            RaceReward::TurboTrackUnlock => String::from("Turbo Track Unlock"),
+            RaceReward::SkullRockUnlock => String::from("Skull Rock Unlock"),
+            RaceReward::RampageRuinsUnlock => String::from("Rampage Ruins Unlock"),
+            RaceReward::RockyRoadUnlock => String::from("Rocky Road Unlock"),
+            RaceReward::NitroCourtUnlock => String::from("Nitro Court Unlock"),
         })
     }
 }
 
+impl std::str::FromStr for RaceReward {
+    type Err = ();
+
+    /// Parse a reward by its display name, see `find_item_by_name`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        find_item_by_name(s, [
+            RaceReward::Trophy,
+            RaceReward::RedCtrToken,
+            RaceReward::GreenCtrToken,
+            RaceReward::BlueCtrToken,
+            RaceReward::YellowCtrToken,
+            RaceReward::PurpleCtrToken,
+            RaceReward::SapphireRelic,
+            RaceReward::GoldRelic,
+            RaceReward::PlatinumRelic,
+            RaceReward::Key,
+            RaceReward::RedGem,
+            RaceReward::GreenGem,
+            RaceReward::BlueGem,
+            RaceReward::YellowGem,
+            RaceReward::PurpleGem,
+            RaceReward::BeatTheGame,
+            RaceReward::Nothing,
+        ]
+        .into_iter()
+        .chain(UNLOCKABLES))
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub enum SettingID {
     RelicDifficulty = 0,
@@ -233,11 +492,14 @@ pub enum SettingID {
     QolSkipMaskhints = 3,
     QolSkipPodium = 4,
     QolSkipMaskcongrats = 5,
+    /// Kept for base mods that predate `OxideFinalChallengeRequirement`
     OxideRequiredRelics = 6,
     SeedHash1 = 7,
     SeedHash2 = 8,
     HelperTiziano = 9,
     HelperTA = 10,
+    Goal = 11,
+    OxideFinalChallengeRequirement = 12,
 }
 
 #[derive(Debug)]
@@ -245,6 +507,8 @@ pub enum SettingValue {
     Boolean(bool),
     RelicDifficulty(RelicTime),
     BossGarageRequirements(BossGarageRequirements),
+    RequirementItem(UnlockRequirementItem),
     OxideRequiredRelics(FinalOxideUnlock),
     SeedHashPart(u16),
+    Goal(Goal),
 }
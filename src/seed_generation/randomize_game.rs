@@ -1,12 +1,12 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 use rand::seq::SliceRandom;
 use rand_chacha::ChaCha8Rng;
 
 use crate::seed_generation::{
-    game_world::{BossCharacter, get_vanilla_gameworld}, item_randomization::randomize_items::get_shuffled_rewards, randomization_datastructures::{
+    game_world::{BossCharacter, Hubs, get_vanilla_gameworld}, gem_cups::get_gem_cup_tracks, hints::get_hints, item_randomization::randomize_items::{get_bonus_unlockables, get_location_list, get_shuffled_rewards}, randomization_datastructures::{
         GameSetup, LevelID, RequiredItem, SettingID, SettingValue, UnlockRequirement, UnlockRequirementItem, UnlockStage
-    }, randomize_warppad_requirements::get_random_warppad_unlocks, seed_settings::{BossGarageRequirements, FinalOxideUnlock, RelicTime, SeedSettings, WarppadUnlockRequirements}
+    }, randomize_warppad_requirements::get_random_warppad_unlocks, relic_times::{get_relic_time_targets, get_vanilla_relic_times}, seed_settings::{BossGarageRequirements, BossGarageShuffle, FinalOxideUnlock, Goal, RelicTime, SeedSettings, WarppadUnlockRequirements}
 };
 
 fn get_vanilla_game() -> GameSetup {
@@ -27,15 +27,26 @@ fn get_vanilla_game() -> GameSetup {
             (SettingID::QolSkipMaskcongrats, SettingValue::Boolean(false)),
             (
                 SettingID::OxideRequiredRelics,
-                SettingValue::OxideRequiredRelics(FinalOxideUnlock::SappireRelics18),
+                SettingValue::OxideRequiredRelics(FinalOxideUnlock::default()),
             ),
+            (
+                SettingID::OxideFinalChallengeRequirement,
+                SettingValue::RequirementItem(FinalOxideUnlock::default().get_requirement()),
+            ),
+            (SettingID::Goal, SettingValue::Goal(Goal::BeatOxide)),
             (SettingID::SeedHash1, SettingValue::SeedHashPart(0x0000)),
             (SettingID::SeedHash2, SettingValue::SeedHashPart(0x0000)),
         ],
+        starting_inventory: Vec::new(),
+        relic_times: get_vanilla_relic_times(),
+        placement_attempts: 0,
+        hints: Vec::new(),
+        gem_cup_tracks: BTreeMap::new(),
+        bonus_unlockables: BTreeMap::new(),
     }
 }
 
-pub fn get_randomized_game(mut seed: ChaCha8Rng, seed_as_number: u32, chosen_settings: &SeedSettings) -> Result<GameSetup, ()> {
+pub fn get_randomized_game(mut seed: ChaCha8Rng, seed_as_number: u32, chosen_settings: &SeedSettings) -> Result<GameSetup, String> {
     let vanilla_gameworld = get_vanilla_game().game_world;
     let mut new_game_world = vanilla_gameworld.clone();
 
@@ -58,21 +69,10 @@ pub fn get_randomized_game(mut seed: ChaCha8Rng, seed_as_number: u32, chosen_set
             (0u16, 0u16)
         };
 
-    let shuffling_ok: Result<(), ()> = if chosen_settings.randomization.shuffle_adventure {
-        // Turbo Track's vanilla warp pad location is in a really awkward to
-        // handle spot if it requires 5 gems and we don't want to do any of the
-        // gem cups for the gems. So as workaround we just force the warp pad
-        // to be completely vanilla in this case.
-        let force_vanilla_turbotrack =
-            matches!(
-                &chosen_settings.randomization.warppad_unlock_requirements,
-                WarppadUnlockRequirements::Vanilla,
-            ) && (chosen_settings.randomization.shuffle_race_rewards.is_none()
-                || !chosen_settings
-                    .randomization
-                    .shuffle_race_rewards
-                    .unwrap()
-                    .include_gems);
+    let mut placement_attempts = 0;
+    let mut bonus_unlockables = BTreeMap::new();
+    let shuffling_ok: Result<(), String> = if chosen_settings.randomization.shuffle_adventure {
+        let force_vanilla_turbotrack = chosen_settings.force_vanilla_turbotrack();
 
         // Warppads
         if let Some(warppad_shuffle) = &chosen_settings.randomization.warppad_shuffle {
@@ -96,7 +96,6 @@ pub fn get_randomized_game(mut seed: ChaCha8Rng, seed_as_number: u32, chosen_set
                 force_vanilla_turbotrack,
             );
 
-            println!("{:?}", new_warppads);
             new_game_world.set_warppad_links(new_warppads);
         }
 
@@ -111,22 +110,38 @@ pub fn get_randomized_game(mut seed: ChaCha8Rng, seed_as_number: u32, chosen_set
             new_game_world.set_garage_unlocks(new_garage_unlocks);
         }
 
+        // Boss Garages
+        // Shuffled after the garage requirements, which stay with their garage
+        if !matches!(chosen_settings.randomization.boss_garage_shuffle, BossGarageShuffle::Vanilla) {
+            let new_boss_hubs = get_shuffled_boss_hubs(
+                &mut seed,
+                new_game_world.get_boss_hubs(),
+                matches!(chosen_settings.randomization.boss_garage_shuffle, BossGarageShuffle::IncludeOxide),
+            );
+
+            new_game_world.set_boss_hubs(new_boss_hubs);
+        }
+
+        // Hub Entrances
+        // N. Sanity Beach stays the starting hub
+        if chosen_settings.randomization.shuffle_hub_entrances {
+            let new_hub_entrances = get_shuffled_hub_entrances(&mut seed, new_game_world.get_hub_entrances());
+
+            new_game_world.set_hub_entrances(new_hub_entrances);
+        }
+
         // Warppad Unlocks
         let mut new_warppad_unlocks = match &chosen_settings.randomization.warppad_unlock_requirements {
             WarppadUnlockRequirements::Vanilla => {
                 vanilla_gameworld.get_warppad_unlocks()
             },
-            x => {
+            _ => {
                 get_random_warppad_unlocks(
                     &mut seed,
-                    x,
-                    &chosen_settings.randomization.shuffle_race_rewards,
+                    chosen_settings,
                     force_vanilla_turbotrack,
-                    new_game_world.get_warppad_links(),
-                    new_game_world.get_warppad_unlocks(),
-                    new_game_world.get_garage_unlocks(),
-                    new_game_world.get_hub_requirements(),
-                ).unwrap()
+                    &new_game_world,
+                )?
             }
         };
 
@@ -138,22 +153,33 @@ pub fn get_randomized_game(mut seed: ChaCha8Rng, seed_as_number: u32, chosen_set
 
         // Race Rewards
         if let Some(reward_shuffle) = &chosen_settings.randomization.shuffle_race_rewards {
-            let new_reward_placement = get_shuffled_rewards(
-                &mut seed,
-                reward_shuffle,
-                force_vanilla_turbotrack,
+            let location_list = get_location_list(
                 &new_game_world.get_warppad_links(),
                 new_game_world.get_warppad_unlocks(),
                 new_game_world.get_garage_unlocks(),
                 new_game_world.get_hub_requirements(),
+                &new_game_world.get_boss_hubs(),
+                chosen_settings.general.oxide_final_challenge_unlock.get_requirement(),
+            );
+            let new_reward_placement = get_shuffled_rewards(
+                &mut seed,
+                chosen_settings,
+                reward_shuffle,
+                force_vanilla_turbotrack,
+                &location_list,
                 !matches!(&chosen_settings.randomization.warppad_unlock_requirements, WarppadUnlockRequirements::Vanilla),
             );
 
-            if let Ok(new_reward_placement) = new_reward_placement {
-                new_game_world.set_rewards(new_reward_placement);
-                Ok(())
-            } else {
-                Err(())
+            match new_reward_placement {
+                Ok((new_reward_placement, attempts)) => {
+                    new_game_world.set_rewards(new_reward_placement);
+                    placement_attempts = attempts;
+                    if chosen_settings.randomization.shuffle_unlockables {
+                        bonus_unlockables = get_bonus_unlockables(&mut seed, &new_game_world.get_race_rewards());
+                    }
+                    Ok(())
+                },
+                Err(msg) => Err(msg),
             }
         } else {
             Ok(())
@@ -162,9 +188,13 @@ pub fn get_randomized_game(mut seed: ChaCha8Rng, seed_as_number: u32, chosen_set
         Ok(())
     };
 
-    if shuffling_ok.is_err() {
-        Err(())
+    if let Err(msg) = shuffling_ok {
+        Err(msg)
     } else {
+        let relic_times = get_relic_time_targets(&mut seed, &chosen_settings.general.relic_times);
+        let hints = get_hints(&mut seed, &new_game_world, chosen_settings);
+        let gem_cup_tracks = get_gem_cup_tracks(&mut seed, chosen_settings.randomization.gem_cup_tracks);
+
         Ok(
             GameSetup {
                 game_world: new_game_world,
@@ -197,10 +227,18 @@ pub fn get_randomized_game(mut seed: ChaCha8Rng, seed_as_number: u32, chosen_set
                     ),
                     (
                         SettingID::OxideRequiredRelics,
-                        SettingValue::OxideRequiredRelics(
-                            chosen_settings.general.oxide_final_challenge_unlock,
+                        SettingValue::OxideRequiredRelics(chosen_settings.general.oxide_final_challenge_unlock),
+                    ),
+                    (
+                        SettingID::OxideFinalChallengeRequirement,
+                        SettingValue::RequirementItem(
+                            chosen_settings.general.oxide_final_challenge_unlock.get_requirement(),
                         ),
                     ),
+                    (
+                        SettingID::Goal,
+                        SettingValue::Goal(chosen_settings.general.goal),
+                    ),
                     (
                         SettingID::SeedHash1,
                         SettingValue::SeedHashPart(overwrite_seed_hash_1),
@@ -218,6 +256,18 @@ pub fn get_randomized_game(mut seed: ChaCha8Rng, seed_as_number: u32, chosen_set
                         SettingValue::Boolean(chosen_settings.tricks.helper_ta),
                     ),
                 ],
+                starting_inventory: chosen_settings
+                    .general
+                    .starting_inventory
+                    .items
+                    .clone()
+                    .into_iter()
+                    .collect(),
+                relic_times,
+                placement_attempts,
+                hints,
+                gem_cup_tracks,
+                bonus_unlockables,
             }
         )
     }
@@ -336,6 +386,45 @@ fn get_shuffled_warppads(
     randomized_levels
 }
 
+fn get_shuffled_boss_hubs(
+    seed: &mut ChaCha8Rng,
+    original_boss_hubs: HashMap<BossCharacter, Hubs>,
+    include_oxide: bool,
+) -> HashMap<BossCharacter, Hubs> {
+    let mut bosses: Vec<BossCharacter> = original_boss_hubs
+        .keys()
+        .filter(|x| include_oxide || **x != BossCharacter::NOxide)
+        .copied()
+        .collect();
+    bosses.sort();
+    let mut hubs: Vec<Hubs> = bosses.iter().map(|x| original_boss_hubs[x]).collect();
+    hubs.shuffle(seed);
+
+    let mut boss_hubs = original_boss_hubs;
+    boss_hubs.extend(bosses.into_iter().zip(hubs));
+
+    boss_hubs
+}
+
+fn get_shuffled_hub_entrances(
+    seed: &mut ChaCha8Rng,
+    original_hub_entrances: HashMap<Hubs, Hubs>,
+) -> HashMap<Hubs, Hubs> {
+    let mut doors: Vec<Hubs> = original_hub_entrances
+        .keys()
+        .filter(|x| **x != Hubs::NSanityBeach)
+        .copied()
+        .collect();
+    doors.sort();
+    let mut hubs: Vec<Hubs> = doors.iter().map(|x| original_hub_entrances[x]).collect();
+    hubs.shuffle(seed);
+
+    let mut hub_entrances = original_hub_entrances;
+    hub_entrances.extend(doors.into_iter().zip(hubs));
+
+    hub_entrances
+}
+
 fn get_modified_garage_unlocks(
     garage_unlock: BossGarageRequirements,
     level_links: HashMap<LevelID, LevelID>,
@@ -444,3 +533,372 @@ fn clear_stage2_unlocks(warppad_unlocks: HashMap<(LevelID, UnlockStage), Option<
 
     modified_warppad_unlocks
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use rand::SeedableRng;
+
+    use super::*;
+    use crate::seed_generation::{
+        gem_cups::{GEM_CUPS, HUB_TRACKS},
+        hint_sheet::{SheetHint, get_hint_sheet},
+        hints::{Hint, get_hub},
+        item_randomization::randomize_items::{get_final_item_placement, get_spheres},
+        randomization_datastructures::{ItemLocation, RaceReward, UNLOCKABLES},
+        seed_estimate::{get_required_locations, get_seed_estimate},
+        seed_settings::GemCupTracks,
+        tricks::get_required_trick,
+    };
+
+    /// The UI defaults with shuffled race rewards, which leaves keys, gems
+    /// and platinum relics at their vanilla locations
+    const DEFAULT_SETTINGS: &str = "rnd1;000;-;0;1;0;0;0;0;0;0;\ngen00018000;\ninv-;\nexc-;\nrlt0;-;\nhnt000000;\n\
+        hsh00000000;000;\npln-;\nqol00000;\ntrk001;\nwrt100;";
+
+    /// The default settings with some of their settings string lines swapped
+    /// out for the given ones
+    fn get_settings(lines: &[&str]) -> SeedSettings {
+        let settings_string = DEFAULT_SETTINGS
+            .lines()
+            .map(|line| lines.iter().find(|x| x[..3] == line[..3]).copied().unwrap_or(line))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let settings = SeedSettings::try_from(settings_string.as_str()).unwrap();
+        settings.validate().unwrap();
+        settings
+    }
+
+    fn get_games(settings: &SeedSettings) -> Vec<GameSetup> {
+        (1..=3)
+            .map(|seed| get_randomized_game(ChaCha8Rng::seed_from_u64(seed), seed as u32, settings).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn excluded_locations_with_vanilla_keys_and_gems() {
+        let settings = get_settings(&["excRelicRaceGold, DingoCanyon TrophyRace;"]);
+        for game in get_games(&settings) {
+            // N. Oxide's final challenge needs every single sapphire relic
+            for (location, reward) in game.game_world.get_race_rewards() {
+                if settings.general.excluded_locations.contains(&location) {
+                    assert_ne!(reward, RaceReward::SapphireRelic, "{location:?}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn default_settings_at_every_logic_level() {
+        for trk in ["trk000;", "trk001;", "trk002;"] {
+            let settings = get_settings(&[trk]);
+            let enabled_tricks = settings.tricks.get_enabled_tricks();
+            for game in get_games(&settings) {
+                // Races that need a trick outside the logic level never hold
+                // anything needed to beat the seed
+                for (location, reward) in game.game_world.get_race_rewards() {
+                    if get_required_trick(&location).is_some_and(|x| !enabled_tricks.contains(&x)) {
+                        assert_ne!(reward, RaceReward::SapphireRelic, "{trk} {location:?}");
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn unlockables_keep_every_race_reward() {
+        let get_sorted_rewards = |race_rewards: HashMap<_, RaceReward>| -> Vec<u32> {
+            let mut rewards: Vec<u32> = race_rewards.into_values().map(|x| x as u32).collect();
+            rewards.sort_unstable();
+            rewards
+        };
+        let vanilla_rewards = get_sorted_rewards(get_vanilla_gameworld().get_race_rewards());
+
+        let settings = get_settings(&["rnd1;000;-;0;1;0;0;1;0;0;0;"]);
+        for game in get_games(&settings) {
+            assert_eq!(game.bonus_unlockables.len(), UNLOCKABLES.len());
+            assert_eq!(get_sorted_rewards(game.game_world.get_race_rewards()), vanilla_rewards);
+        }
+
+        // The filler left behind by the starting inventory goes first, with
+        // no excluded races to take it away
+        let settings = get_settings(&["rnd1;000;-;0;1;0;0;1;0;0;0;", "inv4 Trophies;", "trk002;"]);
+        for game in get_games(&settings) {
+            let race_rewards = game.game_world.get_race_rewards();
+            assert!(!race_rewards.values().any(|x| *x == RaceReward::Nothing));
+            assert_eq!(race_rewards.values().filter(|x| UNLOCKABLES.contains(x)).count(), 4);
+            assert_eq!(game.bonus_unlockables.len(), UNLOCKABLES.len() - 4);
+        }
+    }
+
+    #[test]
+    fn final_challenge_requirements() {
+        // 30 gold or platinum relics, 16 trophies, 20 CTR tokens, 5 gems and
+        // 4 keys, with every item shuffled
+        for gen in ["gen00330000;", "gen00516000;", "gen00620000;", "gen00705000;", "gen00804000;"] {
+            let settings = get_settings(&["rnd1;111;-;0;1;0;0;0;0;0;0;", gen]);
+            let requirement = settings.general.oxide_final_challenge_unlock.get_requirement();
+            for game in get_games(&settings) {
+                assert!(game.settings.iter().any(|x| matches!(
+                    x,
+                    (SettingID::OxideFinalChallengeRequirement, SettingValue::RequirementItem(x)) if *x == requirement,
+                )));
+            }
+        }
+    }
+
+    #[test]
+    fn goals() {
+        // Collect all gems, 18 platinum relics, beat the hub bosses and a
+        // trophy hunt for 12 trophies
+        for gen in ["gen00018100;", "gen00018218;", "gen00018300;", "gen00018412;"] {
+            let settings = get_settings(&["rnd1;111;-;0;1;0;0;0;0;0;0;", gen]);
+            for game in get_games(&settings) {
+                assert!(game.settings.iter().any(|x| matches!(
+                    x,
+                    (SettingID::Goal, SettingValue::Goal(x)) if x.get_id() == settings.general.goal.get_id(),
+                )));
+            }
+        }
+    }
+
+    #[test]
+    fn starting_inventory() {
+        let settings = get_settings(&["rnd1;100;-;0;1;0;0;0;0;0;0;", "inv2 Keys, 4 Trophies;"]);
+        for game in get_games(&settings) {
+            assert_eq!(game.starting_inventory, vec![(RaceReward::Trophy, 4), (RaceReward::Key, 2)]);
+
+            // Starting items are taken out of the race rewards
+            let race_rewards: Vec<RaceReward> = game.game_world.get_race_rewards().into_values().collect();
+            assert_eq!(race_rewards.iter().filter(|x| **x == RaceReward::Nothing).count(), 6);
+            assert_eq!(race_rewards.iter().filter(|x| **x == RaceReward::Trophy).count(), 12);
+            assert_eq!(race_rewards.iter().filter(|x| **x == RaceReward::Key).count(), 2);
+        }
+    }
+
+    #[test]
+    fn item_plando() {
+        let settings = get_settings(&[
+            "rnd1;110;-;0;1;0;0;0;0;0;0;",
+            "plnCrashCove TrophyRace: Key, RoosTubes BossRace: Purple Gem;",
+        ]);
+        for game in get_games(&settings) {
+            let race_rewards = game.game_world.get_race_rewards();
+            for (location, item) in &settings.general.item_plando.placements {
+                assert_eq!(race_rewards[location], *item);
+            }
+        }
+    }
+
+    #[test]
+    fn relic_times() {
+        let settings = get_settings(&["rlt1150;CrashCove 1:10:00/1:00:00/0:50:00;"]);
+        for game in get_games(&settings) {
+            assert_eq!(game.relic_times[&LevelID::CrashCove], settings.general.relic_times.overrides.times[&LevelID::CrashCove]);
+            assert_eq!(
+                game.relic_times[&LevelID::RoosTubes],
+                get_vanilla_relic_times()[&LevelID::RoosTubes].get_scaled(150),
+            );
+        }
+    }
+
+    #[test]
+    fn gentle_difficulty_curve() {
+        // Trophies in the first third of the playthrough
+        let get_early_trophies = |game: &GameSetup, settings: &SeedSettings| -> usize {
+            let item_placement = get_final_item_placement(&game.game_world, settings);
+            let spheres = get_spheres(&item_placement, settings);
+            spheres[..spheres.len().div_ceil(3)]
+                .iter()
+                .flat_map(|x| &x.locations)
+                .filter(|x| item_placement[*x].1 == Some(RaceReward::Trophy))
+                .count()
+        };
+
+        let settings = get_settings(&["rnd1;111;-;1;1;0;0;0;0;0;0;"]);
+        let gentle_settings = get_settings(&["rnd1;111;-;1;1;0;1;0;0;0;0;"]);
+        let early_trophies: Vec<usize> = get_games(&settings).iter().map(|x| get_early_trophies(x, &settings)).collect();
+        let gentle_early_trophies: Vec<usize> = get_games(&gentle_settings)
+            .iter()
+            .map(|x| get_early_trophies(x, &gentle_settings))
+            .collect();
+
+        // The same seeds place the same items, before the curve gets softened
+        assert!(early_trophies.iter().zip(&gentle_early_trophies).all(|(x, gentle)| gentle <= x));
+        assert!(gentle_early_trophies.iter().sum::<usize>() < early_trophies.iter().sum::<usize>());
+    }
+
+    #[test]
+    fn seed_estimate() {
+        let settings = get_settings(&[]);
+        for game in get_games(&settings) {
+            let estimate = get_seed_estimate(&game, &settings);
+            // The final gate needs every sapphire relic
+            let item_placement = get_final_item_placement(&game.game_world, &settings);
+            let sapphire_locations: Vec<ItemLocation> = item_placement
+                .iter()
+                .filter(|(_, x)| x.1 == Some(RaceReward::SapphireRelic))
+                .map(|(location, _)| *location)
+                .collect();
+            assert_eq!(sapphire_locations.len(), 18);
+            assert!(sapphire_locations.iter().all(|x| estimate.required_locations.contains(x)));
+            assert!(estimate.duration_seconds > 0);
+        }
+    }
+
+    #[test]
+    fn hints() {
+        let settings = get_settings(&["rnd1;111;-;1;1;0;0;0;0;0;0;", "hnt040402;", "trk002;"]);
+        for game in get_games(&settings) {
+            let item_placement = get_final_item_placement(&game.game_world, &settings);
+            let required_locations = get_required_locations(&game.game_world, &settings);
+            let warppad_links = game.game_world.get_warppad_links();
+            let boss_hubs = game.game_world.get_boss_hubs();
+
+            assert!(!game.hints.is_empty());
+            for (id, count) in [(0, 4), (1, 4), (2, 2)] {
+                assert!(game.hints.iter().filter(|x| x.get_id() == id).count() <= count);
+            }
+            for hint in &game.hints {
+                match hint {
+                    Hint::ItemInHub { item, hub, .. } => assert!(item_placement.iter().any(|(location, x)| {
+                        x.1 == Some(*item) && get_hub(location, &warppad_links, &boss_hubs) == *hub
+                    })),
+                    Hint::LocationNotRequired(location) => assert!(!required_locations.contains(location)),
+                    Hint::ItemOnRaceType { item, racetype } => assert!(item_placement
+                        .iter()
+                        .any(|(location, x)| x.1 == Some(*item) && location.racetype == *racetype)),
+                }
+            }
+        }
+
+        // Without shuffled race rewards, there is nothing to hint
+        let settings = get_settings(&["rnd1;-;-;0;1;0;0;0;0;0;0;", "hnt040402;"]);
+        assert!(get_games(&settings).iter().all(|x| x.hints.is_empty()));
+    }
+
+    #[test]
+    fn hint_sheet() {
+        let settings = get_settings(&["rnd1;111;-;1;1;0;0;0;0;0;0;", "hsh03020402;010;", "trk002;"]);
+        for (seed, game) in (1..=3).zip(get_games(&settings)) {
+            let item_placement = get_final_item_placement(&game.game_world, &settings);
+            let required_locations = get_required_locations(&game.game_world, &settings);
+            let hint_sheet = get_hint_sheet(seed, &game, &settings);
+
+            assert!(!hint_sheet.is_empty());
+            for (kind, count) in [("way_of_the_hero", 3), ("barren", 2), ("item_location", 4)] {
+                assert!(hint_sheet.iter().filter(|x| x.hint.get_kind() == kind).count() <= count);
+            }
+            for (index, scheduled_hint) in hint_sheet.iter().enumerate() {
+                assert_eq!(scheduled_hint.reveal_minutes, index.saturating_sub(1) as u32 * 10);
+                match scheduled_hint.hint {
+                    SheetHint::WayOfTheHero(location) => assert!(required_locations.contains(&location)),
+                    SheetHint::Barren(levelid) => assert!(!required_locations.iter().any(|x| x.levelid == levelid)),
+                    SheetHint::ItemLocation { location, item } => assert_eq!(item_placement[&location].1, Some(item)),
+                }
+            }
+
+            // The same seed gives the same sheet
+            let hints: Vec<SheetHint> = hint_sheet.iter().map(|x| x.hint).collect();
+            assert!(get_hint_sheet(seed, &game, &settings).iter().map(|x| x.hint).eq(hints));
+        }
+    }
+
+    #[test]
+    fn gem_cup_tracks() {
+        let all_tracks: HashSet<LevelID> = HUB_TRACKS.iter().flatten().copied().collect();
+
+        let settings = get_settings(&[]);
+        assert!(get_games(&settings).iter().all(|x| x.gem_cup_tracks.is_empty()));
+
+        for gem_cup_tracks in 1..=3 {
+            let settings = get_settings(&[&format!("rnd1;000;-;0;1;0;0;0;{gem_cup_tracks};0;0;")]);
+            for game in get_games(&settings) {
+                assert_eq!(game.gem_cup_tracks.keys().copied().collect::<Vec<_>>(), GEM_CUPS);
+                for tracks in game.gem_cup_tracks.values() {
+                    let cup_tracks: HashSet<LevelID> = tracks.iter().copied().collect();
+                    assert_eq!(cup_tracks.len(), 4);
+                    assert!(cup_tracks.is_subset(&all_tracks));
+                }
+
+                let colored_cups: Vec<HashSet<LevelID>> = GEM_CUPS[..4]
+                    .iter()
+                    .map(|x| game.gem_cup_tracks[x].iter().copied().collect())
+                    .collect();
+                match settings.randomization.gem_cup_tracks {
+                    GemCupTracks::NoRepeats => {
+                        assert_eq!(colored_cups.iter().flatten().collect::<HashSet<_>>().len(), 16);
+                    }
+                    GemCupTracks::ByHub => {
+                        assert!(colored_cups
+                            .iter()
+                            .all(|x| HUB_TRACKS.iter().any(|hub| hub.iter().all(|y| x.contains(y)))));
+                        let purple_cup = game.gem_cup_tracks[&LevelID::CupPurple];
+                        assert!(HUB_TRACKS.iter().all(|hub| hub.iter().any(|x| purple_cup.contains(x))));
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn boss_garages() {
+        let vanilla_boss_hubs = get_vanilla_gameworld().get_boss_hubs();
+        let hub_bosses = [
+            BossCharacter::RipperRoo,
+            BossCharacter::PapuPapu,
+            BossCharacter::KomodoJoe,
+            BossCharacter::Pinstripe,
+        ];
+
+        let settings = get_settings(&["rnd1;000;-;0;1;0;0;0;0;1;0;"]);
+        let all_boss_hubs: Vec<HashMap<BossCharacter, Hubs>> =
+            get_games(&settings).iter().map(|x| x.game_world.get_boss_hubs()).collect();
+        assert!(all_boss_hubs.iter().any(|x| *x != vanilla_boss_hubs));
+        for boss_hubs in all_boss_hubs {
+            assert_eq!(boss_hubs[&BossCharacter::NOxide], Hubs::GemStoneValley);
+            let hubs: HashSet<Hubs> = hub_bosses.iter().map(|x| boss_hubs[x]).collect();
+            assert_eq!(hubs, hub_bosses.iter().map(|x| vanilla_boss_hubs[x]).collect());
+        }
+
+        // N. Oxide joining the shuffle needs shuffled keys
+        assert!(SeedSettings::try_from(
+            DEFAULT_SETTINGS.replace("rnd1;000;-;0;1;0;0;0;0;0;0;", "rnd1;000;-;0;1;0;0;0;0;2;0;").as_str()
+        )
+        .unwrap()
+        .validate()
+        .is_err());
+        let settings = get_settings(&["rnd1;100;-;0;1;0;0;0;0;2;0;"]);
+        for game in get_games(&settings) {
+            let boss_hubs = game.game_world.get_boss_hubs();
+            assert_eq!(boss_hubs.len(), 5);
+            assert_eq!(boss_hubs.values().collect::<HashSet<_>>(), vanilla_boss_hubs.values().collect());
+        }
+    }
+
+    #[test]
+    fn hub_entrances() {
+        let vanilla_hub_entrances = get_vanilla_gameworld().get_hub_entrances();
+
+        let settings = get_settings(&["rnd1;000;-;0;1;0;0;0;0;0;1;"]);
+        let all_hub_entrances: Vec<HashMap<Hubs, Hubs>> =
+            get_games(&settings).iter().map(|x| x.game_world.get_hub_entrances()).collect();
+        assert!(all_hub_entrances.iter().any(|x| *x != vanilla_hub_entrances));
+        for hub_entrances in all_hub_entrances {
+            // N. Sanity Beach is where the game starts, so it keeps its door
+            assert_eq!(hub_entrances[&Hubs::NSanityBeach], Hubs::NSanityBeach);
+            assert_eq!(hub_entrances.keys().collect::<HashSet<_>>(), vanilla_hub_entrances.keys().collect());
+            assert_eq!(hub_entrances.values().collect::<HashSet<_>>(), vanilla_hub_entrances.values().collect());
+        }
+
+        // Vanilla race rewards could leave keys behind the doors they open
+        assert!(SeedSettings::try_from(
+            DEFAULT_SETTINGS.replace("rnd1;000;-;0;1;0;0;0;0;0;0;", "rnd1;-;-;0;1;0;0;0;0;0;1;").as_str()
+        )
+        .unwrap()
+        .validate()
+        .is_err());
+    }
+}
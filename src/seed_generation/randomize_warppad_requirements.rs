@@ -4,28 +4,23 @@ use rand::{seq::IndexedRandom, Rng};
 use rand_chacha::ChaCha8Rng;
 
 use crate::seed_generation::{
-    game_world::{get_vanilla_gameworld, BossCharacter, Hubs},
+    game_world::{get_vanilla_gameworld, GameWorld},
     item_randomization::{
         player_inventory::PlayerInventory,
         randomize_items::{get_location_list, get_shuffled_rewards},
     },
     randomization_datastructures::{
-        ItemLocation, LevelID, RaceReward, RaceType, RequiredItem, UnlockRequirement,
-        UnlockRequirementItem, UnlockStage,
+        ItemLocation, LevelID, RaceReward, RaceType, RequiredItem, UnlockRequirementItem, UnlockStage,
     },
-    seed_settings::{RewardShuffle, WarppadUnlockRequirements},
+    seed_settings::{SeedSettings, WarppadUnlockRequirements},
 };
 
 pub fn get_random_warppad_unlocks(
     seed: &mut ChaCha8Rng,
-    requirement_setting: &WarppadUnlockRequirements,
-    opt_reward_shuffle: &Option<RewardShuffle>,
+    chosen_settings: &SeedSettings,
     force_vanilla_turbotrack: bool,
-    warppad_links: HashMap<LevelID, LevelID>,
-    warppad_unlocks: HashMap<(LevelID, UnlockStage), Option<UnlockRequirementItem>>,
-    bossgarage_requirements: HashMap<BossCharacter, UnlockRequirement>,
-    hub_requirements: HashMap<Hubs, Option<UnlockRequirementItem>>,
-) -> Result<HashMap<(LevelID, UnlockStage), Option<UnlockRequirementItem>>, ()> {
+    game_world: &GameWorld,
+) -> Result<HashMap<(LevelID, UnlockStage), Option<UnlockRequirementItem>>, String> {
     fn get_unlock_stage(location: ItemLocation) -> UnlockStage {
         match location.racetype {
             RaceType::TrophyRace | RaceType::BossRace | RaceType::GemCup => UnlockStage::One,
@@ -45,12 +40,15 @@ pub fn get_random_warppad_unlocks(
         }
     }
 
+    let requirement_setting = &chosen_settings.randomization.warppad_unlock_requirements;
+    let opt_reward_shuffle = &chosen_settings.randomization.shuffle_race_rewards;
+
     //
     let mut free_warppads_warppad_unlocks: HashMap<
         (LevelID, UnlockStage),
         Option<UnlockRequirementItem>,
     > = HashMap::new();
-    for ((levelid, stage), _) in warppad_unlocks {
+    for ((levelid, stage), _) in game_world.get_warppad_unlocks() {
         free_warppads_warppad_unlocks.insert(
             (levelid, stage),
             Some(UnlockRequirementItem {
@@ -60,37 +58,37 @@ pub fn get_random_warppad_unlocks(
         );
     }
 
-    let res_zeroed_out_item_placement = if let Some(reward_shuffle) = opt_reward_shuffle {
+    let warppad_links = game_world.get_warppad_links();
+    let mut location_list = get_location_list(
+        &warppad_links,
+        free_warppads_warppad_unlocks,
+        game_world.get_garage_unlocks(),
+        game_world.get_hub_requirements(),
+        &game_world.get_boss_hubs(),
+        chosen_settings.general.oxide_final_challenge_unlock.get_requirement(),
+    );
+
+    let mut zeroed_out_item_placement = if let Some(reward_shuffle) = opt_reward_shuffle {
         get_shuffled_rewards(
             seed,
+            chosen_settings,
             reward_shuffle,
             force_vanilla_turbotrack,
-            &warppad_links,
-            free_warppads_warppad_unlocks.clone(),
-            bossgarage_requirements.clone(),
-            hub_requirements.clone(),
+            &location_list,
             true,
-        )
+        )?
+        .0
     } else {
-        Ok(get_vanilla_gameworld().get_race_rewards())
+        get_vanilla_gameworld().get_race_rewards()
     };
 
-    if res_zeroed_out_item_placement.is_err() {
-        return Err(());
+    // Items on excluded locations can never be required
+    for (location, item) in zeroed_out_item_placement.iter_mut() {
+        if chosen_settings.is_excluded_location(location) {
+            *item = RaceReward::Nothing;
+        }
     }
 
-    let mut zeroed_out_item_placement = res_zeroed_out_item_placement.unwrap();
-
-    // We only have item placements, but are missing static unlock requirements,
-    // so we have to generate those after the fact now.
-    // Sadly `get_shuffled_rewards` does not return its location list too
-    let mut location_list = get_location_list(
-        &warppad_links,
-        free_warppads_warppad_unlocks,
-        bossgarage_requirements,
-        hub_requirements,
-    );
-
     // Filter out 2nd stage unlocks
     // They're currently also set to "free", but we have to set the requirements
     // for the 1st stage first.
@@ -134,10 +132,12 @@ pub fn get_random_warppad_unlocks(
 
     // 2) Starting from there, check which items those races give us, and
     //    randomly choose requirements for the next warp pad(s) from those items
-    let mut inventory = PlayerInventory::new();
+    let mut inventory = PlayerInventory::with_tricks(chosen_settings.tricks.get_enabled_tricks());
+    for item in chosen_settings.general.starting_inventory.get_items() {
+        inventory.add_item(item);
+    }
     let mut filled_locations: Vec<ItemLocation> = Vec::new();
 
-    println!("{:?}", zeroed_out_item_placement);
     for (levelid, _) in random_unlocks.keys() {
         inventory.add_track(*levelid);
 
@@ -185,12 +185,8 @@ pub fn get_random_warppad_unlocks(
                     levelid: *levelid,
                     racetype: RaceType::RelicRacePlatinum,
                 };
-                if let Some(reward_shuffle) = opt_reward_shuffle {
-                    if reward_shuffle.include_platinum_relics {
-                        let item = zeroed_out_item_placement.get(&location).unwrap();
-                        inventory.add_item(*item);
-                    }
-                }
+                let item = zeroed_out_item_placement.get(&location).unwrap();
+                inventory.add_item(*item);
                 zeroed_out_item_placement
                     .remove(&location)
                     .expect("Should be in there");
@@ -295,16 +291,15 @@ pub fn get_random_warppad_unlocks(
                             | RaceReward::PurpleGem
                     ) || (opt_reward_shuffle.is_some()
                         && opt_reward_shuffle.unwrap().include_gems))
+                    && !item.is_filler()
                     && count > &0u8
                 {
                     possible_reqs.push((RequiredItem::try_from(*item).unwrap(), *count));
                 }
             }
             possible_reqs.sort();
-            println!("{:?}", possible_reqs);
             let chosen_reward =
                 possible_reqs.choose_weighted(seed, |x| req_chances.get(&x.0).unwrap());
-            println!("{:?}", chosen_reward);
             let chosen_reward = chosen_reward.unwrap();
             let mut required_item = chosen_reward.0;
             let mut required_amount = chosen_reward.1;
@@ -391,7 +386,24 @@ pub fn get_random_warppad_unlocks(
 
         // Add location's item(s) to inventory
         // This is either one item, or 3 in case of Relic Races
-        inventory.add_track(chosen_location.levelid);
+        // Relic races are checked all at once, so one of them not being
+        // excluded suffices for this track to count as visited
+        let relicraces = [
+            RaceType::RelicRaceSapphire,
+            RaceType::RelicRaceGold,
+            RaceType::RelicRacePlatinum,
+        ];
+        let visited_locations = if relicraces.contains(&chosen_location.racetype) {
+            relicraces
+                .iter()
+                .map(|racetype| ItemLocation { levelid: chosen_location.levelid, racetype: *racetype })
+                .collect()
+        } else {
+            vec![*chosen_location]
+        };
+        if visited_locations.iter().any(|x| !chosen_settings.is_excluded_location(x)) {
+            inventory.add_track(chosen_location.levelid);
+        }
         inventory.add_item(*zeroed_out_item_placement.get(chosen_location).unwrap());
         let _ = zeroed_out_item_placement.remove(chosen_location);
 
@@ -402,7 +414,6 @@ pub fn get_random_warppad_unlocks(
         ]
         .contains(&chosen_location.racetype)
         {
-            //todo platinum not shuffled
             let mut relicraces_to_check: HashSet<RaceType> = HashSet::from([
                 RaceType::RelicRaceSapphire,
                 RaceType::RelicRaceGold,
@@ -490,11 +501,6 @@ pub fn get_random_warppad_unlocks(
 
         if seed.random_range(0..100) < 66 {
             if req.count != 0 {
-                println!(
-                    "Lowering {:?} {}",
-                    req,
-                    ((req.count as f32) * 0.6).ceil() as u8
-                );
                 unlock_modifications.insert(
                     k.clone(),
                     Some(UnlockRequirementItem {
@@ -510,7 +516,6 @@ pub fn get_random_warppad_unlocks(
                 WarppadUnlockRequirements::RandomWithout4Keys
             )
         {
-            println!("Setting '4 keys' requirement to '3 keys' {:?}", req,);
             unlock_modifications.insert(
                 k.clone(),
                 Some(UnlockRequirementItem {
@@ -0,0 +1,157 @@
+use std::collections::BTreeMap;
+
+use rand::Rng;
+use rand_chacha::ChaCha8Rng;
+
+use crate::seed_generation::{
+    randomization_datastructures::LevelID,
+    seed_settings::{RelicTimeSettings, RelicTimeTargets},
+};
+
+/// Relic race times of a single track, in centiseconds
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RelicTimes {
+    pub sapphire: u32,
+    pub gold: u32,
+    pub platinum: u32,
+}
+
+impl RelicTimes {
+    /// Scale all three times by the given percentage, rounded to a tenth of
+    /// a second
+    pub fn get_scaled(&self, percent: u32) -> RelicTimes {
+        let scale = |x: u32| ((x * percent + 500) / 1000) * 10;
+
+        RelicTimes {
+            sapphire: scale(self.sapphire),
+            gold: scale(self.gold),
+            platinum: scale(self.platinum),
+        }
+    }
+}
+
+impl std::fmt::Display for RelicTimes {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}/{}/{}",
+            format_relic_time(self.sapphire),
+            format_relic_time(self.gold),
+            format_relic_time(self.platinum),
+        )
+    }
+}
+
+/// Vanilla relic race data of a single track
+#[derive(Debug, Clone, Copy)]
+pub struct RelicTrackData {
+    pub levelid: LevelID,
+    pub vanilla_times: RelicTimes,
+    pub num_boxes: u8,
+    /// Total time saved by breaking all boxes, without the bonus for a perfect
+    pub box_time_save: u32,
+}
+
+const fn relic_track(
+    levelid: LevelID,
+    sapphire: u32,
+    gold: u32,
+    platinum: u32,
+    num_boxes: u8,
+    box_time_save: u32,
+) -> RelicTrackData {
+    RelicTrackData {
+        levelid,
+        vanilla_times: RelicTimes { sapphire, gold, platinum },
+        num_boxes,
+        box_time_save,
+    }
+}
+
+/// Vanilla relic race times, as listed in the handbook
+pub const RELIC_TRACK_DATA: [RelicTrackData; 18] = [
+    relic_track(LevelID::CrashCove, 7700, 6500, 5200, 32, 5700),
+    relic_track(LevelID::RoosTubes, 7500, 6500, 5500, 32, 4800),
+    relic_track(LevelID::MysteryCaves, 11500, 10400, 9200, 40, 6200),
+    relic_track(LevelID::SewerSpeedway, 9300, 6500, 3700, 65, 12100),
+    relic_track(LevelID::TigerTemple, 8000, 6200, 4300, 52, 10100),
+    relic_track(LevelID::CocoPark, 9500, 7200, 4900, 40, 6400),
+    relic_track(LevelID::PapusPyramid, 9400, 6900, 4200, 48, 9400),
+    relic_track(LevelID::DingoCanyon, 8500, 6900, 5300, 38, 6000),
+    relic_track(LevelID::BlizzardBluff, 9000, 6800, 4500, 40, 7000),
+    relic_track(LevelID::DragonMines, 8800, 7100, 5400, 39, 6600),
+    relic_track(LevelID::PolarPass, 18000, 15300, 12500, 38, 6800),
+    relic_track(LevelID::TinyArena, 22500, 20200, 17800, 60, 9000),
+    relic_track(LevelID::NGinLabs, 13500, 9400, 5300, 70, 12400),
+    relic_track(LevelID::CortexCastle, 15500, 12400, 9200, 62, 10000),
+    relic_track(LevelID::HotAirSkyway, 18500, 15400, 12200, 56, 8800),
+    relic_track(LevelID::OxideStation, 19700, 17600, 15400, 49, 7500),
+    relic_track(LevelID::SlideColiseum, 11500, 10500, 10000, 45, 7100),
+    relic_track(LevelID::TurboTrack, 10500, 9200, 7900, 52, 8500),
+];
+
+pub fn get_relic_track_data(levelid: LevelID) -> Option<RelicTrackData> {
+    RELIC_TRACK_DATA.iter().find(|x| x.levelid == levelid).copied()
+}
+
+pub fn get_vanilla_relic_times() -> BTreeMap<LevelID, RelicTimes> {
+    RELIC_TRACK_DATA
+        .iter()
+        .map(|x| (x.levelid, x.vanilla_times))
+        .collect()
+}
+
+/// Format centiseconds the way the game displays relic times, e.g. "1:17:00"
+pub fn format_relic_time(centiseconds: u32) -> String {
+    format!(
+        "{}:{:02}:{:02}",
+        centiseconds / 6000,
+        (centiseconds / 100) % 60,
+        centiseconds % 100,
+    )
+}
+
+/// Parse a relic time like "1:17:00" into centiseconds
+pub fn parse_relic_time(time: &str) -> Option<u32> {
+    let parts: Vec<u32> = time
+        .trim()
+        .split(':')
+        .map(|x| x.parse::<u32>().ok())
+        .collect::<Option<Vec<u32>>>()?;
+
+    match parts[..] {
+        [minutes, seconds, centiseconds] if seconds < 60 && centiseconds < 100 => {
+            Some(minutes * 6000 + seconds * 100 + centiseconds)
+        }
+        _ => None,
+    }
+}
+
+/// Decide on the relic times of every relic race track. Overrides take
+/// precedence over the chosen time targets.
+pub fn get_relic_time_targets(
+    seed: &mut ChaCha8Rng,
+    relic_time_settings: &RelicTimeSettings,
+) -> BTreeMap<LevelID, RelicTimes> {
+    let mut relic_times = BTreeMap::new();
+
+    for track in RELIC_TRACK_DATA {
+        let times = match relic_time_settings.targets {
+            RelicTimeTargets::Vanilla => track.vanilla_times,
+            RelicTimeTargets::Scaled(percent) => track.vanilla_times.get_scaled(u32::from(percent)),
+            RelicTimeTargets::Randomized(min_percent, max_percent) => {
+                // One factor for all three times keeps them in order
+                let percent = seed.random_range(min_percent..=max_percent);
+                track.vanilla_times.get_scaled(u32::from(percent))
+            }
+        };
+
+        relic_times.insert(track.levelid, times);
+    }
+
+    for (levelid, times) in &relic_time_settings.overrides.times {
+        relic_times.insert(*levelid, *times);
+    }
+
+    relic_times
+}
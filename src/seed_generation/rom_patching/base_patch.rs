@@ -0,0 +1,121 @@
+use std::path::{Path, PathBuf};
+
+use crate::seed_generation::version::DB_VERSION;
+
+pub const BUILTIN_BASE_PATCH: &[u8] = include_bytes!("../../../res/base_patch.bsdiff4");
+const BUILTIN_MANIFEST: &str = include_str!("../../../res/base_patch.json");
+
+/// Start of every bsdiff patch, to tell base patches and patched ROMs apart
+const BSDIFF_MAGIC: &[u8; 8] = b"BSDIFF40";
+
+/// What the base mod expects of the database, read from the manifest that
+/// comes with every base patch
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BaseManifest {
+    pub db_version: u16,
+    /// ROM offset the database is written to
+    pub db_offset: u64,
+    /// Bytes the base mod reserves for the database at db_offset
+    pub db_max_size: u64,
+}
+
+#[derive(Debug, Clone)]
+pub enum BaseSource {
+    Builtin,
+    /// bsdiff patch for the vanilla ROM
+    PatchFile(PathBuf),
+    /// Vanilla ROM with the base mod already applied
+    PatchedRom(PathBuf),
+}
+
+/// The base mod seeds are built on, either the one built into the generator
+/// or one from disk, for trying out changes to the mod without rebuilding
+/// the generator
+#[derive(Debug, Clone)]
+pub struct BasePatch {
+    pub source: BaseSource,
+    pub manifest: BaseManifest,
+    /// MD5 of the base patch or patched ROM
+    pub hash: String,
+}
+
+impl BasePatch {
+    pub fn builtin() -> Self {
+        BasePatch {
+            source: BaseSource::Builtin,
+            manifest: parse_manifest(BUILTIN_MANIFEST).expect("built-in manifest is valid"),
+            hash: format!("{:x}", md5::compute(BUILTIN_BASE_PATCH)),
+        }
+    }
+
+    /// Load a base patch or patched ROM, along with its manifest: the file
+    /// of the same name ending in .json, e.g. base_patch.json for
+    /// base_patch.bsdiff4
+    pub fn from_file(path: &Path) -> Result<Self, String> {
+        let bytes = std::fs::read(path).map_err(|_| "Could not read the base patch!".to_owned())?;
+
+        let manifest_path = path.with_extension("json");
+        let manifest = std::fs::read_to_string(&manifest_path)
+            .map_err(|_| format!("Could not read the base patch manifest\n{}!", manifest_path.display()))
+            .and_then(|x| parse_manifest(&x))?;
+        if manifest.db_version != DB_VERSION {
+            return Err(format!(
+                "The base patch expects database version {},\n\
+                    this generator writes database version {DB_VERSION}!",
+                manifest.db_version,
+            ));
+        }
+
+        let source = if bytes.starts_with(BSDIFF_MAGIC) {
+            BaseSource::PatchFile(path.to_path_buf())
+        } else if manifest.db_offset + manifest.db_max_size <= bytes.len() as u64 {
+            BaseSource::PatchedRom(path.to_path_buf())
+        } else {
+            return Err("The database space of the manifest lies outside of the patched ROM!".to_owned());
+        };
+
+        Ok(BasePatch {
+            source,
+            manifest,
+            hash: format!("{:x}", md5::compute(bytes)),
+        })
+    }
+
+    /// The bsdiff patch for the vanilla ROM, None for a patched ROM
+    pub fn get_patch_data(&self) -> Result<Option<Vec<u8>>, String> {
+        match &self.source {
+            BaseSource::Builtin => Ok(Some(BUILTIN_BASE_PATCH.to_vec())),
+            BaseSource::PatchFile(path) => std::fs::read(path)
+                .map(Some)
+                .map_err(|_| "Could not read the base patch!".to_owned()),
+            BaseSource::PatchedRom(_) => Ok(None),
+        }
+    }
+}
+
+/// Read a manifest like
+/// `{"db_version": 1, "db_offset": "0xF220", "db_max_size": "0x1000"}`,
+/// where the offset and size may also be plain numbers
+fn parse_manifest(text: &str) -> Result<BaseManifest, String> {
+    let invalid = |field: &str| format!("The base patch manifest has no valid '{field}'!");
+    let manifest: serde_json::Value = serde_json::from_str(text)
+        .map_err(|_| "The base patch manifest is not valid JSON!".to_owned())?;
+
+    let db_version = manifest["db_version"]
+        .as_u64()
+        .and_then(|x| u16::try_from(x).ok())
+        .ok_or_else(|| invalid("db_version"))?;
+    let get_number = |field: &str| -> Result<u64, String> {
+        match &manifest[field] {
+            serde_json::Value::String(x) => x
+                .strip_prefix("0x")
+                .and_then(|x| u64::from_str_radix(x, 16).ok()),
+            x => x.as_u64(),
+        }
+        .ok_or_else(|| invalid(field))
+    };
+    let db_offset = get_number("db_offset")?;
+    let db_max_size = get_number("db_max_size")?;
+
+    Ok(BaseManifest { db_version, db_offset, db_max_size })
+}
@@ -1,52 +1,100 @@
-use std::{io, path::PathBuf};
+use std::{io, path::{Path, PathBuf}};
 
 use qbsdiff::{Bsdiff, Bspatch};
 
-pub fn apply_base_patchfile(old_rom_path: &str, seed: u32) -> Result<PathBuf, Box<dyn std::error::Error>> {
-    let patchdata = include_bytes!("../../../res/base_patch.bsdiff4");
+use crate::seed_generation::{
+    output_paths::OutputSettings,
+    rom_patching::base_patch::{BaseManifest, BasePatch, BaseSource},
+    version::{check_version_info, get_version_info},
+    write_rando_db::{get_db_seed, read_database},
+};
 
-    apply_patch(old_rom_path, SeedOrFilename::Seed(seed), Vec::from(patchdata))
+/// Patch files stay plain bsdiff patches, the versions they were made with
+/// are written next to them as JSON: the file of the same name ending in
+/// .json, like the manifest of a base patch
+pub fn get_version_info_path(patch_file_path: &Path) -> PathBuf {
+    patch_file_path.with_extension("json")
 }
-enum SeedOrFilename {
-    Seed(u32),
-    Filename(String),
+
+/// Write the base modded ROM to new_rom_path, see OutputSettings for where
+/// that is
+pub fn apply_base_patchfile(old_rom_path: &str, new_rom_path: &Path, base_patch: &BasePatch) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    match base_patch.get_patch_data()? {
+        Some(patchdata) => apply_patch(old_rom_path, new_rom_path, patchdata),
+        // A patched ROM only needs to be copied, the vanilla ROM is not used
+        None => {
+            let BaseSource::PatchedRom(base_rom_path) = &base_patch.source else {
+                unreachable!("only patched ROMs come without patch data");
+            };
+            write_new_rom(new_rom_path, &std::fs::read(base_rom_path)?)
+        }
+    }
 }
 
-pub fn apply_patchfile(old_rom_path: &str, patch_file_path: &str) -> Result<PathBuf, Box<dyn std::error::Error>> {
+/// Apply a patch file shared by another player, writing the new ROM where
+/// the output settings say. Also returns a warning if the patch file was made
+/// by a different generator version.
+pub fn apply_patchfile(
+    old_rom_path: &str,
+    patch_file_path: &str,
+    manifest: &BaseManifest,
+    output_settings: &OutputSettings,
+) -> Result<(PathBuf, Option<String>), Box<dyn std::error::Error>> {
     let patch_file_path = PathBuf::from(patch_file_path);
 
-    let file_stem = patch_file_path.clone();
-    let file_stem = format!("{}{}", file_stem.file_stem().unwrap().to_str().unwrap(), ".bin") ;
+    // Patch files of older generators, or shared without their version info,
+    // have no version info to check
+    let version_info = std::fs::read(get_version_info_path(&patch_file_path))
+        .ok()
+        .and_then(|x| serde_json::from_slice(&x).ok())
+        .unwrap_or_default();
+    let warning = check_version_info(&version_info, "patch file")?;
+
+    let new_rom = get_patched_rom(old_rom_path, std::fs::read(&patch_file_path)?)?;
+
+    // Name the new ROM like a generated seed, unless the seed hash is hidden
+    let seed = usize::try_from(manifest.db_offset)
+        .ok()
+        .and_then(|x| new_rom.get(x..))
+        .and_then(read_database)
+        .and_then(|(database, _)| get_db_seed(&database));
+    let new_rom_path = match seed {
+        Some(seed) => output_settings.get_new_rom_path(Path::new(old_rom_path), seed)?,
+        None => output_settings.get_patched_rom_path(Path::new(old_rom_path), &patch_file_path)?,
+    };
+    let new_rom_path = write_new_rom(&new_rom_path, &new_rom)?;
 
-    let patchdata = std::fs::read(patch_file_path).unwrap();
+    Ok((new_rom_path, warning))
+}
 
-    apply_patch(old_rom_path, SeedOrFilename::Filename(file_stem), patchdata)
+fn apply_patch(old_rom_path: &str, new_rom_path: &Path, patchdata: Vec<u8>) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    write_new_rom(new_rom_path, &get_patched_rom(old_rom_path, patchdata)?)
 }
 
-fn apply_patch(old_rom_path: &str, seed_or_filename: SeedOrFilename, patchdata: Vec<u8>) -> Result<PathBuf, Box<dyn std::error::Error>> {
+fn get_patched_rom(old_rom_path: &str, patchdata: Vec<u8>) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
     let old_rom = std::fs::read(old_rom_path)?;
     let mut new_rom = Vec::new();
 
     let mut patchdata_array: [u8; 1000000] = [0u8; 1000000]; // jank bs
+    if patchdata.len() > patchdata_array.len() {
+        return Err("The patch file is too large!".into());
+    }
     for (i, byte) in patchdata.iter().enumerate() {
         patchdata_array[i] = *byte;
     }
     let patcher = Bspatch::new(&patchdata_array)?;
     patcher.apply(&old_rom, io::Cursor::new(&mut new_rom))?;
 
-    let new_rom_name = match seed_or_filename {
-        SeedOrFilename::Seed(seed) => format!("{}{}{}", "CTR-Randomizer_", seed, ".bin"),
-        SeedOrFilename::Filename(filename) => filename,
-    };
-    let mut new_rom_path = PathBuf::from(old_rom_path);
-    new_rom_path.pop();
-    new_rom_path.push(new_rom_name);
-    std::fs::write(&new_rom_path, &new_rom)?;
+    Ok(new_rom)
+}
+
+fn write_new_rom(new_rom_path: &Path, new_rom: &[u8]) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    std::fs::write(new_rom_path, new_rom)?;
 
-    Ok(new_rom_path)
+    Ok(new_rom_path.to_path_buf())
 }
 
-pub fn create_patchfile(old_rom_path: &str, new_rom_path: &PathBuf) -> Result<PathBuf, Box<dyn std::error::Error>> {
+pub fn create_patchfile(old_rom_path: &str, new_rom_path: &PathBuf, base_patch: &BasePatch) -> Result<PathBuf, Box<dyn std::error::Error>> {
     let mut patch = Vec::new();
     let old_rom = std::fs::read(old_rom_path)?;
     let new_rom = std::fs::read(new_rom_path)?;
@@ -60,5 +108,6 @@ pub fn create_patchfile(old_rom_path: &str, new_rom_path: &PathBuf) -> Result<Pa
     Bsdiff::new(&old_rom, &new_rom).compare(io::Cursor::new(&mut patch))?;
 
     std::fs::write(&patchfile_path, patch)?;
+    std::fs::write(get_version_info_path(&patchfile_path), get_version_info(base_patch).to_string())?;
     Ok(patchfile_path)
 }
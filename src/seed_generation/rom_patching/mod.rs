@@ -1 +1,2 @@
+pub mod base_patch;
 pub mod bsdiff_patching;
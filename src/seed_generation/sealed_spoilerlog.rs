@@ -0,0 +1,91 @@
+use std::{io, path::{Path, PathBuf}};
+
+use chacha20poly1305::{
+    aead::{Aead, KeyInit, Payload},
+    ChaCha20Poly1305, Key, Nonce,
+};
+use serde_json::to_string_pretty;
+use sha2::Sha256;
+
+use crate::seed_generation::{
+    html_spoilerlog::write_html_spoilerlog, spoilerlog::get_spoilerlog_path, version::check_version_info,
+};
+
+/// Start of every sealed spoiler log, ending in its format version
+const SEAL_MAGIC: &[u8; 8] = b"CTRSEAL1";
+const SALT_SIZE: usize = 16;
+const NONCE_SIZE: usize = 12;
+const HEADER_SIZE: usize = SEAL_MAGIC.len() + SALT_SIZE + NONCE_SIZE;
+
+/// PBKDF2 rounds for turning the passphrase into the key, to make guessing
+/// the passphrase slow during the race
+const KEY_ROUNDS: u32 = 600_000;
+
+/// Encrypt the spoiler log with the passphrase. The sealed log is the header
+/// (magic, salt, nonce) followed by the encrypted JSON, which also
+/// authenticates the header.
+pub fn seal_spoilerlog(spoilerlog: &serde_json::Value, passphrase: &str) -> Vec<u8> {
+    let salt: [u8; SALT_SIZE] = rand::random();
+    let nonce: [u8; NONCE_SIZE] = rand::random();
+
+    let mut sealed = Vec::with_capacity(HEADER_SIZE);
+    sealed.extend_from_slice(SEAL_MAGIC);
+    sealed.extend_from_slice(&salt);
+    sealed.extend_from_slice(&nonce);
+
+    let plaintext = to_string_pretty(spoilerlog).unwrap();
+    let ciphertext = get_cipher(passphrase, &salt)
+        .encrypt(Nonce::from_slice(&nonce), Payload { msg: plaintext.as_bytes(), aad: &sealed })
+        .expect("encrypting into a Vec cannot fail");
+    sealed.extend(ciphertext);
+
+    sealed
+}
+
+pub fn unseal_spoilerlog(sealed: &[u8], passphrase: &str) -> Result<serde_json::Value, String> {
+    if sealed.len() < HEADER_SIZE || !sealed.starts_with(SEAL_MAGIC) {
+        return Err("The file is not a sealed spoiler log!".to_owned());
+    }
+    let (header, ciphertext) = sealed.split_at(HEADER_SIZE);
+    let salt = &header[SEAL_MAGIC.len()..SEAL_MAGIC.len() + SALT_SIZE];
+    let nonce = &header[SEAL_MAGIC.len() + SALT_SIZE..];
+
+    let plaintext = get_cipher(passphrase, salt)
+        .decrypt(Nonce::from_slice(nonce), Payload { msg: ciphertext, aad: header })
+        .map_err(|_| "Wrong passphrase, or the sealed spoiler log is damaged!".to_owned())?;
+
+    serde_json::from_slice(&plaintext).map_err(|_| "The sealed spoiler log holds no valid spoiler log!".to_owned())
+}
+
+/// Write the sealed spoiler log next to the new ROM, in place of the spoiler
+/// log itself
+pub fn write_sealed_spoilerlog(new_rom_path: &Path, spoilerlog: &serde_json::Value, passphrase: &str) -> Result<(), io::Error> {
+    std::fs::write(
+        get_spoilerlog_path(new_rom_path, "sealed"),
+        seal_spoilerlog(spoilerlog, passphrase),
+    )
+}
+
+/// Unseal a sealed spoiler log after the race, writing the spoiler log and
+/// its web page next to it. Also returns a warning if it was sealed by a different generator
+/// version, which never keeps the spoiler log locked.
+pub fn unlock_spoilerlog(sealed_path: &Path, passphrase: &str) -> Result<(PathBuf, Option<String>), String> {
+    let sealed = std::fs::read(sealed_path).map_err(|_| "Could not read the sealed spoiler log!".to_owned())?;
+    let spoilerlog = unseal_spoilerlog(&sealed, passphrase)?;
+    let warning = check_version_info(&spoilerlog["version"], "spoiler log").unwrap_or_else(Some);
+
+    let spoilerlog_path = sealed_path.with_extension("json");
+    std::fs::write(&spoilerlog_path, to_string_pretty(&spoilerlog).unwrap())
+        .map_err(|_| "Could not create spoiler log file!".to_owned())?;
+    write_html_spoilerlog(&sealed_path.with_extension("html"), &spoilerlog)
+        .map_err(|_| "Could not create spoiler log file!".to_owned())?;
+
+    Ok((spoilerlog_path, warning))
+}
+
+fn get_cipher(passphrase: &str, salt: &[u8]) -> ChaCha20Poly1305 {
+    let mut key = [0u8; 32];
+    pbkdf2::pbkdf2_hmac::<Sha256>(passphrase.as_bytes(), salt, KEY_ROUNDS, &mut key);
+
+    ChaCha20Poly1305::new(Key::from_slice(&key))
+}
@@ -0,0 +1,143 @@
+use std::collections::BTreeMap;
+
+use crate::seed_generation::{
+    game_world::GameWorld,
+    item_randomization::randomize_items::{get_final_item_placement, get_spheres, is_beatable},
+    randomization_datastructures::{GameSetup, ItemLocation, LevelID, RaceType},
+    relic_times::{get_relic_track_data, RELIC_TRACK_DATA},
+    seed_settings::SeedSettings,
+};
+
+/// Seconds for loading, the hub, and the podium around every race
+const SECONDS_PER_RACE_OVERHEAD: u32 = 60;
+/// Crystal challenges run on a fixed timer
+const SECONDS_PER_CRYSTAL_CHALLENGE: u32 = 120;
+/// Relic races usually take a couple of attempts
+const RELIC_RACE_ATTEMPTS: u32 = 3;
+
+/// Rough estimate of how long a seed takes to beat, based on the smallest set
+/// of races needed to reach the goal
+pub struct SeedEstimate {
+    pub required_locations: Vec<ItemLocation>,
+    /// Trophy races, CTR challenges, and crystal challenges
+    pub num_races: usize,
+    /// Relic races, counting all relic tiers of a track as one race
+    pub num_relic_races: usize,
+    pub num_gem_cups: usize,
+    pub num_boss_races: usize,
+    pub duration_seconds: u32,
+}
+
+impl std::fmt::Display for SeedEstimate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} races, {} relic races, {} cups, {} boss races, ~{}h {:02}m",
+            self.num_races,
+            self.num_relic_races,
+            self.num_gem_cups,
+            self.num_boss_races,
+            self.duration_seconds / 3600,
+            (self.duration_seconds / 60) % 60,
+        )
+    }
+}
+
+/// Find the required races by walking the playthrough backwards and leaving
+/// out every race the goal can still be reached without.
+pub fn get_required_locations(game_world: &GameWorld, chosen_settings: &SeedSettings) -> Vec<ItemLocation> {
+    let mut item_placement = get_final_item_placement(game_world, chosen_settings);
+
+    let spheres = get_spheres(&item_placement, chosen_settings);
+    if !is_beatable(&item_placement, chosen_settings) {
+        return spheres.into_iter().flat_map(|x| x.locations).collect();
+    }
+
+    let mut required_locations = Vec::new();
+    for location in spheres.into_iter().rev().flat_map(|x| x.locations.into_iter().rev()) {
+        let removed = item_placement.remove(&location).expect("every sphere location is placed");
+        if !is_beatable(&item_placement, chosen_settings) {
+            item_placement.insert(location, removed);
+            required_locations.push(location);
+        }
+    }
+
+    required_locations.reverse();
+    required_locations
+}
+
+pub fn get_seed_estimate(game_setup: &GameSetup, chosen_settings: &SeedSettings) -> SeedEstimate {
+    let required_locations = get_required_locations(&game_setup.game_world, chosen_settings);
+
+    let get_race_seconds = |levelid: LevelID| {
+        get_relic_track_data(levelid).map_or(SECONDS_PER_CRYSTAL_CHALLENGE, |x| x.vanilla_times.sapphire / 100)
+    };
+    let average_race_seconds = RELIC_TRACK_DATA
+        .iter()
+        .map(|x| x.vanilla_times.sapphire / 100)
+        .sum::<u32>()
+        / RELIC_TRACK_DATA.len() as u32;
+
+    let mut num_races = 0;
+    let mut num_gem_cups = 0;
+    let mut num_boss_races = 0;
+    // Hardest relic time needed per track: 0 Sapphire, 1 Gold, 2 Platinum
+    let mut relic_races: BTreeMap<LevelID, u16> = BTreeMap::new();
+    let mut duration_seconds = 0;
+
+    for location in &required_locations {
+        match location.racetype {
+            RaceType::TrophyRace | RaceType::CtrOrCrystalChallenge => {
+                num_races += 1;
+                duration_seconds += get_race_seconds(location.levelid);
+            }
+            RaceType::BossRace => {
+                num_boss_races += 1;
+                duration_seconds += get_race_seconds(location.levelid);
+            }
+            RaceType::GemCup => {
+                num_gem_cups += 1;
+                duration_seconds += match game_setup.gem_cup_tracks.get(&location.levelid) {
+                    Some(tracks) => tracks
+                        .iter()
+                        .map(|x| get_race_seconds(*x) + SECONDS_PER_RACE_OVERHEAD)
+                        .sum(),
+                    None => 4 * (average_race_seconds + SECONDS_PER_RACE_OVERHEAD),
+                };
+            }
+            RaceType::RelicRaceSapphire | RaceType::RelicRaceGold | RaceType::RelicRacePlatinum => {
+                let tier = match location.racetype {
+                    RaceType::RelicRaceSapphire => 0,
+                    RaceType::RelicRaceGold => 1,
+                    _ => 2,
+                }
+                .max(chosen_settings.general.rr_required_minimum_time as u16);
+                let relic_tier = relic_races.entry(location.levelid).or_default();
+                *relic_tier = (*relic_tier).max(tier);
+            }
+        }
+    }
+
+    for (levelid, tier) in &relic_races {
+        let Some(times) = game_setup.relic_times.get(levelid) else {
+            continue;
+        };
+        let relic_time = match tier {
+            0 => times.sapphire,
+            1 => times.gold,
+            _ => times.platinum,
+        };
+        duration_seconds += RELIC_RACE_ATTEMPTS * (relic_time / 100 + SECONDS_PER_RACE_OVERHEAD);
+    }
+
+    duration_seconds += (num_races + num_boss_races) as u32 * SECONDS_PER_RACE_OVERHEAD;
+
+    SeedEstimate {
+        num_races,
+        num_relic_races: relic_races.len(),
+        num_gem_cups,
+        num_boss_races,
+        duration_seconds,
+        required_locations,
+    }
+}
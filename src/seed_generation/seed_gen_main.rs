@@ -1,66 +1,101 @@
-use rand::SeedableRng;
+use rand::{RngCore, SeedableRng};
 use rand_chacha::ChaCha8Rng;
 
+use crate::seed_generation::game_world::GameWorld;
+use crate::seed_generation::hint_sheet::{get_hint_sheet, write_hint_sheet};
+use crate::seed_generation::output_paths::OutputSettings;
 use crate::seed_generation::randomize_game::get_randomized_game;
+use crate::seed_generation::rom_patching::base_patch::BasePatch;
 use crate::seed_generation::rom_patching::bsdiff_patching::{apply_base_patchfile, create_patchfile};
+use crate::seed_generation::seed_estimate::get_seed_estimate;
 use crate::seed_generation::seed_settings::SeedSettings;
-use crate::seed_generation::spoilerlog::{get_seed_hash, write_spoilerlog};
+use crate::seed_generation::sealed_spoilerlog::write_sealed_spoilerlog;
+use crate::seed_generation::version::get_generator_version;
+use crate::seed_generation::spoilerlog::{get_seed_hash, get_spoilerlog, write_spoilerlog};
 use crate::seed_generation::write_rando_db::write_db_to_rom;
 
 use std::time::Instant;
 
 
 pub struct SeedMetadata {
+    pub seed: u32,
+    pub new_rom_path: std::path::PathBuf,
     pub seed_filename: String,
     pub seed_hash: String,
+    /// Empty in race mode, as it hints at the seed's contents
+    pub seed_estimate: String,
+    /// The generated world, for tracking a playthrough of the seed. None in
+    /// race mode.
+    pub game_world: Option<GameWorld>,
 }
 
 
-pub fn generate_seed<'a>(rom_filepath: &'a str, chosen_settings: &'a SeedSettings) -> Result<SeedMetadata, String> {
-    let now = Instant::now();
+pub fn generate_seed<'a>(
+    rom_filepath: &'a str,
+    chosen_settings: &'a SeedSettings,
+    race_passphrase: &str,
+    base_patch: &BasePatch,
+    output_settings: &OutputSettings,
+) -> Result<SeedMetadata, String> {
+    chosen_settings.validate()?;
+    if chosen_settings.race_mode && race_passphrase.is_empty() {
+        return Err("Race mode needs a passphrase for sealing the spoiler log!".to_owned());
+    }
 
-    let mut seed: u32;
-    loop {
-        seed = rand::random::<u32>();
+    let now = Instant::now();
 
-        if seed != 0u32 {
-            break;
-        }
-    }
-    let seed: u32 = seed;
+    let (seed, rng) = get_seed_rng(get_random_seed(), chosen_settings);
     println!("seed: {seed}");
 
     // randomize game
-    let rng = ChaCha8Rng::seed_from_u64(u64::from(seed));
     let randomized_game = get_randomized_game(rng, seed, chosen_settings);
 
     let elapsed = now.elapsed();
     println!("Elapsed: {:.2?}", elapsed);
 
     if let Ok(randomized_game) = randomized_game {
+        let seed_estimate = get_seed_estimate(&randomized_game, chosen_settings);
+        let game_world = randomized_game.game_world.clone();
+
         // apply base mod patch to rom
-        let filepath_new_rom = apply_base_patchfile(rom_filepath, seed);
+        let new_rom_path = output_settings.get_new_rom_path(std::path::Path::new(rom_filepath), seed)?;
+        let filepath_new_rom = apply_base_patchfile(rom_filepath, &new_rom_path, base_patch);
 
         match filepath_new_rom {
             Ok(new_rom) => {
                 // write randomization to rom
-                let write_result = write_db_to_rom(&new_rom, &randomized_game);
+                let write_result = write_db_to_rom(&new_rom, &randomized_game, &base_patch.manifest);
                 if write_result.is_err() {
                     return Err(write_result.expect_err("str type error").to_owned());
                 }
 
                 // if needed, write patch file
                 if chosen_settings.write_patchfile {
-                    let filepath_new_patch = create_patchfile(rom_filepath, &new_rom);
+                    let filepath_new_patch = create_patchfile(rom_filepath, &new_rom, base_patch);
 
                     if filepath_new_patch.is_err() {
                         return Err("Could not create patch file!".to_owned());
                     }
                 }
 
-                // if needed, write spoiler log
-                if chosen_settings.write_spoilerlog {
-                    let log_success = write_spoilerlog(&new_rom, randomized_game, seed, chosen_settings);
+                // if needed, write hint sheet
+                if chosen_settings.general.hint_sheet.get_total() > 0 {
+                    let hint_sheet = get_hint_sheet(seed, &randomized_game, chosen_settings);
+
+                    if write_hint_sheet(&new_rom, seed, &hint_sheet).is_err() {
+                        return Err("Could not create hint sheet files!".to_owned());
+                    }
+                }
+
+                // if needed, write spoiler log, sealed in race mode
+                if chosen_settings.race_mode {
+                    let spoilerlog = get_spoilerlog(randomized_game, seed, chosen_settings, &seed_estimate, base_patch);
+
+                    if write_sealed_spoilerlog(&new_rom, &spoilerlog, race_passphrase).is_err() {
+                        return Err("Could not create sealed spoiler log file!".to_owned());
+                    }
+                } else if chosen_settings.write_spoilerlog {
+                    let log_success = write_spoilerlog(&new_rom, randomized_game, seed, chosen_settings, &seed_estimate, base_patch);
 
                     if log_success.is_err() {
                         return Err("Could not create spoiler log file!".to_owned());
@@ -69,8 +104,12 @@ pub fn generate_seed<'a>(rom_filepath: &'a str, chosen_settings: &'a SeedSetting
 
                 return Ok(
                     SeedMetadata {
+                        seed,
                         seed_filename: new_rom.file_name().unwrap().to_string_lossy().to_string(),
                         seed_hash: get_seed_hash(seed),
+                        seed_estimate: if chosen_settings.race_mode {String::new()} else {seed_estimate.to_string()},
+                        game_world: (!chosen_settings.race_mode).then_some(game_world),
+                        new_rom_path: new_rom,
                     }
                 );
             },
@@ -86,7 +125,39 @@ pub fn generate_seed<'a>(rom_filepath: &'a str, chosen_settings: &'a SeedSetting
             Version: {}\n\
             Settings:\n{}",
         seed,
-        "beta 2",
+        get_generator_version(),
         chosen_settings
     ))
 }
+
+/// The seed a game is randomized with, along with its RNG. In race mode the
+/// given seed is mixed with fresh entropy: the returned seed, which is
+/// written to the ROM and used in file names, is the lower half of the mixed
+/// value, while the RNG follows from all of it. So the seed hash still tells
+/// games apart, but the seed cannot be used to generate the game again and
+/// look up its items.
+pub fn get_seed_rng(seed: u32, chosen_settings: &SeedSettings) -> (u32, ChaCha8Rng) {
+    if !chosen_settings.race_mode {
+        return (seed, ChaCha8Rng::seed_from_u64(u64::from(seed)));
+    }
+
+    loop {
+        let race_rng_seed = ChaCha8Rng::seed_from_u64(u64::from(seed) ^ rand::random::<u64>()).next_u64();
+        let race_seed = race_rng_seed as u32;
+
+        if race_seed != 0u32 {
+            return (race_seed, ChaCha8Rng::seed_from_u64(race_rng_seed));
+        }
+    }
+}
+
+/// Pick a new seed, never 0 as that stands for "no seed hash" in the ROM
+pub fn get_random_seed() -> u32 {
+    loop {
+        let seed = rand::random::<u32>();
+
+        if seed != 0u32 {
+            return seed;
+        }
+    }
+}
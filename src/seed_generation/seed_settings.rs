@@ -1,3 +1,13 @@
+use std::collections::BTreeMap;
+
+use crate::seed_generation::game_world::get_vanilla_gameworld;
+use crate::seed_generation::item_randomization::item_pool::{build_item_pool, get_preplaced_items};
+use crate::seed_generation::relic_times::{get_relic_track_data, parse_relic_time, RelicTimes};
+use crate::seed_generation::tricks::{get_required_trick, Trick, TRICKS};
+use crate::seed_generation::randomization_datastructures::{
+    ItemLocation, LevelID, RaceReward, RaceType, RequiredItem, UnlockRequirementItem
+};
+
 pub struct SeedSettings {
     pub randomization: RandomizationSettings,
     pub general: GeneralSettings,
@@ -5,6 +15,269 @@ pub struct SeedSettings {
     pub tricks: TrickSettings,
     pub write_spoilerlog: bool,
     pub write_patchfile: bool,
+    /// Seal the spoiler log with a passphrase instead of writing it
+    pub race_mode: bool,
+}
+
+impl SeedSettings {
+    /// Turbo Track's vanilla warp pad location is in a really awkward to
+    /// handle spot if it requires 5 gems and we don't want to do any of the
+    /// gem cups for the gems. So as workaround we force the warp pad to be
+    /// completely vanilla in this case.
+    pub fn force_vanilla_turbotrack(&self) -> bool {
+        matches!(
+            self.randomization.warppad_unlock_requirements,
+            WarppadUnlockRequirements::Vanilla,
+        ) && self.randomization.shuffle_race_rewards.is_none_or(|x| !x.include_gems)
+    }
+
+    /// Whether the item on this location must never be required by the
+    /// logic. Besides the user's exclusions, this covers platinum relic races
+    /// if platinum relics aren't shuffled, as we assume the player does not
+    /// want to do them, and races needing a trick above the logic level.
+    pub fn is_excluded_location(&self, location: &ItemLocation) -> bool {
+        let plats_shuffled = self.randomization.shuffle_race_rewards.is_some_and(|x| x.include_platinum_relics);
+
+        (location.racetype == RaceType::RelicRacePlatinum && !plats_shuffled)
+            || self.general.excluded_locations.contains(location)
+            || get_required_trick(location).is_some_and(|x| !self.tricks.get_enabled_tricks().contains(&x))
+    }
+
+    /// Check for setting combinations that can never produce a seed.
+    /// Returns a message meant to be shown to the user otherwise.
+    pub fn validate(&self) -> Result<(), String> {
+        let final_unlock = self.general.oxide_final_challenge_unlock;
+
+        let mut max_final_count = final_unlock.item_type.get_max_count();
+        if self.randomization.shuffle_adventure {
+            if let Some(reward_shuffle) = self.randomization.shuffle_race_rewards {
+                // Unshuffled platinum relics are never expected of the player
+                if !reward_shuffle.include_platinum_relics {
+                    max_final_count = match final_unlock.item_type {
+                        RequiredItem::PlatinumRelic => 0,
+                        RequiredItem::GoldOrPlatinumRelic => 18,
+                        RequiredItem::AnyRelic => 36,
+                        _ => max_final_count,
+                    };
+                }
+            }
+        }
+        // The ROM database stores requirement counts in 5 bits
+        let max_final_count = max_final_count.min(0x1F);
+        let goal = self.general.goal;
+        if let Goal::PlatinumRelics(_) = goal {
+            if self.randomization.shuffle_adventure
+                && self.randomization.shuffle_race_rewards.is_some_and(|x| !x.include_platinum_relics)
+            {
+                return Err("The goal 'Platinum Relics' requires platinum relics\n\
+                    to be shuffled when shuffling race rewards!".to_owned());
+            }
+        }
+        if let Goal::PlatinumRelics(count) | Goal::TrophyHunt(count) = goal {
+            let max_goal_count = if let Goal::PlatinumRelics(_) = goal {18} else {16};
+            if count == 0 || count > max_goal_count {
+                return Err(format!(
+                    "The goal '{}' needs a count between 1 and {}!",
+                    goal,
+                    max_goal_count,
+                ));
+            }
+        }
+
+        for (item, count) in &self.general.starting_inventory.items {
+            let shuffled = match self.randomization.shuffle_race_rewards {
+                Some(reward_shuffle) if self.randomization.shuffle_adventure => match item {
+                    RaceReward::Key => reward_shuffle.include_keys,
+                    RaceReward::RedGem
+                    | RaceReward::GreenGem
+                    | RaceReward::BlueGem
+                    | RaceReward::YellowGem
+                    | RaceReward::PurpleGem => reward_shuffle.include_gems,
+                    RaceReward::PlatinumRelic => reward_shuffle.include_platinum_relics,
+                    _ => true,
+                },
+                _ => false,
+            };
+            if !shuffled {
+                return Err(format!(
+                    "Starting with {}(s) requires {}(s)\n\
+                        to be shuffled as race rewards!",
+                    item,
+                    item,
+                ));
+            }
+
+            // Turbo Track keeps its relics if forced to be vanilla
+            let mut max_count = RequiredItem::try_from(*item).map_or(0, |x| x.get_max_count());
+            if self.force_vanilla_turbotrack()
+                && matches!(item, RaceReward::SapphireRelic | RaceReward::GoldRelic | RaceReward::PlatinumRelic)
+            {
+                max_count -= 1;
+            }
+            if *count > max_count {
+                return Err(format!(
+                    "Cannot start with more than {} {}(s)\n\
+                        with the chosen settings!",
+                    max_count,
+                    item,
+                ));
+            }
+        }
+
+        // Vanilla race rewards can leave the keys behind the doors they open
+        if self.randomization.shuffle_adventure
+            && self.randomization.shuffle_hub_entrances
+            && self.randomization.shuffle_race_rewards.is_none()
+        {
+            return Err("Shuffling hub entrances requires\n\
+                race rewards to be shuffled!".to_owned());
+        }
+
+        // Unlockables are only handed out while placing the race rewards
+        if self.randomization.shuffle_adventure
+            && self.randomization.shuffle_unlockables
+            && self.randomization.shuffle_race_rewards.is_none()
+        {
+            return Err("Shuffling unlockables requires\n\
+                race rewards to be shuffled!".to_owned());
+        }
+
+        // A hub boss in Oxide's garage would hold a key behind the 4 keys
+        if self.randomization.shuffle_adventure
+            && matches!(self.randomization.boss_garage_shuffle, BossGarageShuffle::IncludeOxide)
+            && !self.randomization.shuffle_race_rewards.is_some_and(|x| x.include_keys)
+        {
+            return Err("Shuffling N. Oxide into the boss garages requires\n\
+                keys to be shuffled as race rewards!".to_owned());
+        }
+
+        if matches!(goal, Goal::BeatOxide)
+            && self.is_excluded_location(&ItemLocation {
+                levelid: LevelID::OxideStation,
+                racetype: RaceType::BossRace,
+            })
+        {
+            return Err("Oxide's Final Challenge cannot be excluded\n\
+                if beating it is the goal!".to_owned());
+        }
+
+        // The logic never collects items from excluded locations, and every
+        // key is needed to open the hubs and Oxide's garage
+        if let Some(reward_shuffle) = self.randomization.shuffle_race_rewards.filter(|_| self.randomization.shuffle_adventure) {
+            let preplaced_items = get_preplaced_items(&reward_shuffle, self.force_vanilla_turbotrack());
+            let mut locked_keys: Vec<&ItemLocation> = preplaced_items
+                .iter()
+                .chain(&self.general.item_plando.placements)
+                .filter(|(location, item)| **item == RaceReward::Key && self.is_excluded_location(location))
+                .map(|(location, _)| location)
+                .collect();
+            locked_keys.sort();
+            if let Some(location) = locked_keys.first() {
+                return Err(format!(
+                    "'{} {}' cannot be excluded while it holds a Key,\n\
+                        every key is needed to open the hubs!",
+                    location.levelid,
+                    location.racetype,
+                ));
+            }
+        }
+
+        let item_plando = &self.general.item_plando.placements;
+        if !item_plando.is_empty() {
+            let reward_shuffle = match self.randomization.shuffle_race_rewards {
+                Some(x) if self.randomization.shuffle_adventure => x,
+                _ => return Err("Placing items by hand requires\n\
+                    race rewards to be shuffled!".to_owned()),
+            };
+
+            let all_locations = get_vanilla_gameworld().get_race_rewards();
+            let preplaced_items = get_preplaced_items(&reward_shuffle, self.force_vanilla_turbotrack());
+            for location in item_plando.keys() {
+                if !all_locations.contains_key(location) {
+                    return Err(format!(
+                        "'{} {}' is not a valid location!",
+                        location.levelid,
+                        location.racetype,
+                    ));
+                }
+                if let Some(item) = preplaced_items.get(location) {
+                    return Err(format!(
+                        "'{} {}' already holds a {} that\n\
+                            is not shuffled with the chosen settings!",
+                        location.levelid,
+                        location.racetype,
+                        item,
+                    ));
+                }
+            }
+
+            let item_pool = build_item_pool(
+                &reward_shuffle,
+                self.force_vanilla_turbotrack(),
+                &self.general.starting_inventory.get_items(),
+            );
+            let mut plando_counts: BTreeMap<RaceReward, usize> = BTreeMap::new();
+            for item in item_plando.values() {
+                *plando_counts.entry(*item).or_default() += 1;
+            }
+            for (item, count) in plando_counts {
+                let pool_count = item_pool.iter().filter(|x| **x == item).count();
+                if count > pool_count {
+                    return Err(format!(
+                        "Cannot place more than {} {}(s) by hand\n\
+                            with the chosen settings!",
+                        pool_count,
+                        item,
+                    ));
+                }
+            }
+        }
+
+        match self.general.relic_times.targets {
+            RelicTimeTargets::Randomized(min_percent, max_percent) if min_percent > max_percent => {
+                return Err("The lower bound of randomized relic times\n\
+                    cannot be above the upper bound!".to_owned());
+            }
+            RelicTimeTargets::Scaled(percent) | RelicTimeTargets::Randomized(percent, _)
+                if percent < MIN_RELIC_TIME_PERCENT =>
+            {
+                return Err(format!("Relic times cannot be scaled below {MIN_RELIC_TIME_PERCENT}%!"));
+            }
+            RelicTimeTargets::Scaled(percent) | RelicTimeTargets::Randomized(_, percent)
+                if percent > MAX_RELIC_TIME_PERCENT =>
+            {
+                return Err(format!("Relic times cannot be scaled above {MAX_RELIC_TIME_PERCENT}%!"));
+            }
+            _ => (),
+        }
+        for (levelid, times) in &self.general.relic_times.overrides.times {
+            // The ROM database stores relic times as u16
+            if times.sapphire < times.gold || times.gold < times.platinum || times.platinum == 0
+                || times.sapphire > u32::from(u16::MAX)
+            {
+                return Err(format!(
+                    "The relic times for {} need to be\n\
+                        Sapphire >= Gold >= Platinum > 0:00:00, and at most 10:55:35!",
+                    levelid,
+                ));
+            }
+        }
+
+        if self.general.hints.get_total() > MAX_HINTS {
+            return Err(format!("There cannot be more than {MAX_HINTS} hints!"));
+        }
+
+        if final_unlock.count > max_final_count {
+            return Err(format!(
+                "Oxide's Final Challenge cannot require more than {} {}(s)\n\
+                    with the chosen settings!",
+                max_final_count,
+                final_unlock.item_type,
+            ));
+        }
+
+        Ok(())
+    }
 }
 
 impl std::fmt::Display for SeedSettings {
@@ -52,6 +325,30 @@ impl std::fmt::Display for SeedSettings {
 
             setting_representation.push(if self.randomization.autounlock_ctrchallenge_relicrace {'1'} else {'0'});
             setting_representation.push(';');
+
+            setting_representation.push(if self.randomization.gentle_difficulty_curve {'1'} else {'0'});
+            setting_representation.push(';');
+
+            setting_representation.push(if self.randomization.shuffle_unlockables {'1'} else {'0'});
+            setting_representation.push(';');
+
+            setting_representation.push(match self.randomization.gem_cup_tracks {
+                GemCupTracks::Vanilla => '0',
+                GemCupTracks::Random => '1',
+                GemCupTracks::NoRepeats => '2',
+                GemCupTracks::ByHub => '3',
+            });
+            setting_representation.push(';');
+
+            setting_representation.push(match self.randomization.boss_garage_shuffle {
+                BossGarageShuffle::Vanilla => '0',
+                BossGarageShuffle::HubBosses => '1',
+                BossGarageShuffle::IncludeOxide => '2',
+            });
+            setting_representation.push(';');
+
+            setting_representation.push(if self.randomization.shuffle_hub_entrances {'1'} else {'0'});
+            setting_representation.push(';');
         }
         setting_representation.push('\n');
 
@@ -62,11 +359,68 @@ impl std::fmt::Display for SeedSettings {
             RelicTime::PlatinumTime => '2',
         });
         setting_representation.push(if self.general.rr_require_perfects {'1'} else {'0'});
-        setting_representation.push(match self.general.oxide_final_challenge_unlock {
-            FinalOxideUnlock::SappireRelics18 => '0',
-            FinalOxideUnlock::GoldAndPlatinumRelics18 => '1',
+        setting_representation.push_str(&format!(
+            "{}{:02}",
+            FINAL_OXIDE_UNLOCK_ITEMS
+                .iter()
+                .position(|x| *x == self.general.oxide_final_challenge_unlock.item_type)
+                .unwrap_or_default(),
+            self.general.oxide_final_challenge_unlock.count,
+        ));
+        setting_representation.push_str(&format!(
+            "{}{:02}",
+            self.general.goal.get_id(),
+            self.general.goal.get_count(),
+        ));
+        setting_representation.push(';');
+        setting_representation.push('\n');
+
+        setting_representation.push_str("inv");
+        setting_representation.push_str(&self.general.starting_inventory.to_string());
+        setting_representation.push(';');
+        setting_representation.push('\n');
+
+        setting_representation.push_str("exc");
+        setting_representation.push_str(&self.general.excluded_locations.to_string());
+        setting_representation.push(';');
+        setting_representation.push('\n');
+
+        setting_representation.push_str("rlt");
+        setting_representation.push_str(&match self.general.relic_times.targets {
+            RelicTimeTargets::Vanilla => String::from("0"),
+            RelicTimeTargets::Scaled(x) => format!("1{x:03}"),
+            RelicTimeTargets::Randomized(x, y) => format!("2{x:03}{y:03}"),
         });
         setting_representation.push(';');
+        setting_representation.push_str(&self.general.relic_times.overrides.to_string());
+        setting_representation.push(';');
+        setting_representation.push('\n');
+
+        setting_representation.push_str("hnt");
+        setting_representation.push_str(&format!(
+            "{:02}{:02}{:02}",
+            self.general.hints.item_area_hints,
+            self.general.hints.not_required_hints,
+            self.general.hints.race_type_hints,
+        ));
+        setting_representation.push(';');
+        setting_representation.push('\n');
+
+        setting_representation.push_str("hsh");
+        setting_representation.push_str(&format!(
+            "{:02}{:02}{:02}{:02};{:03}",
+            self.general.hint_sheet.way_of_the_hero_hints,
+            self.general.hint_sheet.barren_hints,
+            self.general.hint_sheet.item_location_hints,
+            self.general.hint_sheet.starting_hints,
+            self.general.hint_sheet.reveal_interval_minutes,
+        ));
+        setting_representation.push(';');
+        setting_representation.push('\n');
+
+        setting_representation.push_str("pln");
+        setting_representation.push_str(&self.general.item_plando.to_string());
+        setting_representation.push(';');
         setting_representation.push('\n');
 
         setting_representation.push_str("qol");
@@ -81,18 +435,208 @@ impl std::fmt::Display for SeedSettings {
         setting_representation.push_str("trk");
         setting_representation.push(if self.tricks.helper_tiziano {'1'} else {'0'});
         setting_representation.push(if self.tricks.helper_ta {'1'} else {'0'});
+        setting_representation.push(match self.tricks.logic_level {
+            LogicLevel::Beginner => '0',
+            LogicLevel::Standard => '1',
+            LogicLevel::Expert => '2',
+        });
         setting_representation.push(';');
         setting_representation.push('\n');
 
         setting_representation.push_str("wrt");
         setting_representation.push(if self.write_spoilerlog {'1'} else {'0'});
         setting_representation.push(if self.write_patchfile {'1'} else {'0'});
+        setting_representation.push(if self.race_mode {'1'} else {'0'});
         setting_representation.push(';');
 
         write!(f, "{}", setting_representation)
     }
 }
 
+impl TryFrom<&str> for SeedSettings {
+    type Error = String;
+
+    /// Parse a settings string, as produced by the `Display` implementation
+    fn try_from(v: &str) -> Result<Self, Self::Error> {
+        fn get_line<'a>(v: &'a str, prefix: &str) -> Result<Vec<&'a str>, String> {
+            v.lines()
+                .find_map(|line| line.trim().strip_prefix(prefix))
+                .map(|line| line.split(';').collect())
+                .ok_or(format!("Settings string is missing the '{prefix}' line!"))
+        }
+        fn get_digits(v: &str, prefix: &str) -> Result<Vec<i32>, String> {
+            v.chars()
+                .map(|x| x.to_digit(10).map(|x| x as i32))
+                .collect::<Option<Vec<i32>>>()
+                .ok_or(format!("Invalid '{prefix}' settings '{v}'!"))
+        }
+        fn get_list(v: &str) -> &str {
+            if v == "-" { "" } else { v }
+        }
+        let invalid = |prefix: &str| format!("Invalid '{prefix}' settings!");
+
+        let rnd = get_line(v, "rnd")?;
+        let randomization = if rnd[0] == "-" {
+            RandomizationSettings {
+                shuffle_adventure: false,
+                shuffle_race_rewards: None,
+                warppad_shuffle: None,
+                warppad_unlock_requirements: WarppadUnlockRequirements::Vanilla,
+                bossgarage_unlock_requirements: BossGarageRequirements::SameHubTracks,
+                autounlock_ctrchallenge_relicrace: false,
+                gentle_difficulty_curve: false,
+                shuffle_unlockables: false,
+                gem_cup_tracks: GemCupTracks::Vanilla,
+                boss_garage_shuffle: BossGarageShuffle::Vanilla,
+                shuffle_hub_entrances: false,
+            }
+        } else {
+            let [_, reward_shuffle, warppad_shuffle, warppad_unlocks, bossgarage_unlocks, autounlock, gentle_curve, unlockables, gem_cup_tracks, boss_garages, hub_entrances, ..] = rnd[..] else {
+                return Err(invalid("rnd"));
+            };
+            let shuffle_race_rewards = match reward_shuffle {
+                "-" => None,
+                x => match get_digits(x, "rnd")?[..] {
+                    [keys, gems, plats] => Some(RewardShuffle {
+                        include_keys: keys == 1,
+                        include_gems: gems == 1,
+                        include_platinum_relics: plats == 1,
+                    }),
+                    _ => return Err(invalid("rnd")),
+                },
+            };
+            let warppad_shuffle = match warppad_shuffle {
+                "-" => None,
+                x => match get_digits(x, "rnd")?[..] {
+                    [arenas, gemcups] => Some(WarppadShuffle {
+                        include_battle_arenas: arenas == 1,
+                        include_gem_cups: gemcups == 1,
+                    }),
+                    _ => return Err(invalid("rnd")),
+                },
+            };
+
+            RandomizationSettings {
+                shuffle_adventure: true,
+                shuffle_race_rewards,
+                warppad_shuffle,
+                warppad_unlock_requirements: warppad_unlocks
+                    .parse::<i32>()
+                    .ok()
+                    .and_then(|x| WarppadUnlockRequirements::try_from(x).ok())
+                    .ok_or(invalid("rnd"))?,
+                bossgarage_unlock_requirements: bossgarage_unlocks
+                    .parse::<i32>()
+                    .ok()
+                    .and_then(|x| BossGarageRequirements::try_from(x).ok())
+                    .ok_or(invalid("rnd"))?,
+                autounlock_ctrchallenge_relicrace: autounlock == "1",
+                gentle_difficulty_curve: gentle_curve == "1",
+                shuffle_unlockables: unlockables == "1",
+                gem_cup_tracks: gem_cup_tracks
+                    .parse::<i32>()
+                    .ok()
+                    .and_then(|x| GemCupTracks::try_from(x).ok())
+                    .ok_or(invalid("rnd"))?,
+                boss_garage_shuffle: boss_garages
+                    .parse::<i32>()
+                    .ok()
+                    .and_then(|x| BossGarageShuffle::try_from(x).ok())
+                    .ok_or(invalid("rnd"))?,
+                shuffle_hub_entrances: hub_entrances == "1",
+            }
+        };
+
+        let gen = get_digits(get_line(v, "gen")?[0], "gen")?;
+        let [rr_time, rr_perfects, final_item, final_count_1, final_count_2, goal, goal_count_1, goal_count_2] = gen[..] else {
+            return Err(invalid("gen"));
+        };
+
+        let rlt = get_line(v, "rlt")?;
+        let relic_time_targets = match get_digits(rlt[0], "rlt")?[..] {
+            [0] => RelicTimeTargets::Vanilla,
+            [1, a, b, c] => RelicTimeTargets::try_from((1, a * 100 + b * 10 + c, 0))
+                .map_err(|()| invalid("rlt"))?,
+            [2, a, b, c, d, e, f] => RelicTimeTargets::try_from((2, a * 100 + b * 10 + c, d * 100 + e * 10 + f))
+                .map_err(|()| invalid("rlt"))?,
+            _ => return Err(invalid("rlt")),
+        };
+
+        let hnt = get_digits(get_line(v, "hnt")?[0], "hnt")?;
+        let [item_area_1, item_area_2, not_required_1, not_required_2, race_type_1, race_type_2] = hnt[..] else {
+            return Err(invalid("hnt"));
+        };
+
+        let hsh = get_line(v, "hsh")?;
+        let [hero_1, hero_2, barren_1, barren_2, item_location_1, item_location_2, starting_1, starting_2] =
+            get_digits(hsh[0], "hsh")?[..]
+        else {
+            return Err(invalid("hsh"));
+        };
+        let reveal_interval = hsh.get(1).and_then(|x| x.parse::<i32>().ok()).ok_or(invalid("hsh"))?;
+
+        let general = GeneralSettings {
+            rr_required_minimum_time: RelicTime::try_from(rr_time).map_err(|()| invalid("gen"))?,
+            rr_require_perfects: rr_perfects == 1,
+            oxide_final_challenge_unlock: FinalOxideUnlock::try_from((final_item, final_count_1 * 10 + final_count_2))
+                .map_err(|()| invalid("gen"))?,
+            goal: Goal::try_from((goal, goal_count_1 * 10 + goal_count_2)).map_err(|()| invalid("gen"))?,
+            starting_inventory: StartingInventory::try_from(get_list(get_line(v, "inv")?[0]))?,
+            excluded_locations: ExcludedLocations::try_from(get_list(get_line(v, "exc")?[0]))?,
+            item_plando: ItemPlando::try_from(get_list(get_line(v, "pln")?[0]))?,
+            relic_times: RelicTimeSettings {
+                targets: relic_time_targets,
+                overrides: RelicTimeOverrides::try_from(get_list(rlt.get(1).copied().unwrap_or_default()))?,
+            },
+            hints: HintSettings::try_from((
+                item_area_1 * 10 + item_area_2,
+                not_required_1 * 10 + not_required_2,
+                race_type_1 * 10 + race_type_2,
+            ))
+            .map_err(|()| invalid("hnt"))?,
+            hint_sheet: HintSheetSettings::try_from((
+                hero_1 * 10 + hero_2,
+                barren_1 * 10 + barren_2,
+                item_location_1 * 10 + item_location_2,
+                starting_1 * 10 + starting_2,
+                reveal_interval,
+            ))
+            .map_err(|()| invalid("hsh"))?,
+        };
+
+        let qol = get_digits(get_line(v, "qol")?[0], "qol")?;
+        let [skip_mask_hints, autoskip_podium, skip_mask_congrats, ..] = qol[..] else {
+            return Err(invalid("qol"));
+        };
+        let trk = get_digits(get_line(v, "trk")?[0], "trk")?;
+        let [helper_tiziano, helper_ta, logic_level] = trk[..] else {
+            return Err(invalid("trk"));
+        };
+        let wrt = get_digits(get_line(v, "wrt")?[0], "wrt")?;
+        let [write_spoilerlog, write_patchfile, race_mode] = wrt[..] else {
+            return Err(invalid("wrt"));
+        };
+
+        Ok(SeedSettings {
+            randomization,
+            general,
+            qol: QualityOfLifeSettings {
+                skip_mask_hints: skip_mask_hints == 1,
+                autoskip_podium_cutscenes: autoskip_podium == 1,
+                skip_mask_congrats: skip_mask_congrats == 1,
+            },
+            tricks: TrickSettings {
+                helper_tiziano: helper_tiziano == 1,
+                helper_ta: helper_ta == 1,
+                logic_level: LogicLevel::try_from(logic_level).map_err(|()| invalid("trk"))?,
+            },
+            write_spoilerlog: write_spoilerlog == 1,
+            write_patchfile: write_patchfile == 1,
+            race_mode: race_mode == 1,
+        })
+    }
+}
+
 pub struct RandomizationSettings {
     pub shuffle_adventure: bool,
     pub shuffle_race_rewards: Option<RewardShuffle>,
@@ -100,12 +644,28 @@ pub struct RandomizationSettings {
     pub warppad_unlock_requirements: WarppadUnlockRequirements,
     pub bossgarage_unlock_requirements: BossGarageRequirements,
     pub autounlock_ctrchallenge_relicrace: bool,
+    pub gentle_difficulty_curve: bool,
+    /// Shuffle unlockable characters, arcade tracks and battle arenas into
+    /// the filler of the item pool, or on top of race rewards
+    pub shuffle_unlockables: bool,
+    pub gem_cup_tracks: GemCupTracks,
+    pub boss_garage_shuffle: BossGarageShuffle,
+    /// Shuffle which hub is behind the key doors of Lost Ruins, Glacier Park,
+    /// Citadel City and Gem Stone Valley
+    pub shuffle_hub_entrances: bool,
 }
 
 pub struct GeneralSettings {
     pub rr_required_minimum_time: RelicTime,
     pub rr_require_perfects: bool,
     pub oxide_final_challenge_unlock: FinalOxideUnlock,
+    pub goal: Goal,
+    pub starting_inventory: StartingInventory,
+    pub excluded_locations: ExcludedLocations,
+    pub item_plando: ItemPlando,
+    pub relic_times: RelicTimeSettings,
+    pub hints: HintSettings,
+    pub hint_sheet: HintSheetSettings,
 }
 
 pub struct QualityOfLifeSettings {
@@ -117,6 +677,50 @@ pub struct QualityOfLifeSettings {
 pub struct TrickSettings {
     pub helper_tiziano: bool,
     pub helper_ta: bool,
+    pub logic_level: LogicLevel,
+}
+
+impl TrickSettings {
+    /// The tricks the logic expects the player to know
+    pub fn get_enabled_tricks(&self) -> Vec<Trick> {
+        TRICKS
+            .into_iter()
+            .filter(|x| x.get_logic_level(self) <= self.logic_level)
+            .collect()
+    }
+}
+
+/// How much player skill the logic expects
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LogicLevel {
+    /// No tricks
+    Beginner = 0,
+    Standard = 1,
+    /// Every trick
+    Expert = 2,
+}
+
+impl std::fmt::Display for LogicLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            LogicLevel::Beginner => String::from("Beginner"),
+            LogicLevel::Standard => String::from("Standard"),
+            LogicLevel::Expert => String::from("Expert"),
+        })
+    }
+}
+
+impl TryFrom<i32> for LogicLevel {
+    type Error = ();
+
+    fn try_from(v: i32) -> Result<Self, Self::Error> {
+        match v {
+            x if x == LogicLevel::Beginner as i32 => Ok(LogicLevel::Beginner),
+            x if x == LogicLevel::Standard as i32 => Ok(LogicLevel::Standard),
+            x if x == LogicLevel::Expert as i32 => Ok(LogicLevel::Expert),
+            _ => Err(()),
+        }
+    }
 }
 
 #[derive(Clone, Copy)]
@@ -166,11 +770,121 @@ impl std::fmt::Display for BossGarageRequirements {
     }
 }
 
+/// Which bosses get shuffled between the boss garages
 #[derive(Debug, Clone, Copy)]
-#[repr(u16)]
-pub enum FinalOxideUnlock {
-    SappireRelics18 = 0,
-    GoldAndPlatinumRelics18 = 1,
+pub enum BossGarageShuffle {
+    Vanilla = 0,
+    /// Ripper Roo, Papu Papu, Komodo Joe and Pinstripe swap hub garages
+    HubBosses = 1,
+    /// N. Oxide and his Gem Stone Valley garage join the shuffle
+    IncludeOxide = 2,
+}
+
+impl std::fmt::Display for BossGarageShuffle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            BossGarageShuffle::Vanilla => String::from("Vanilla"),
+            BossGarageShuffle::HubBosses => String::from("HubBosses"),
+            BossGarageShuffle::IncludeOxide => String::from("IncludeOxide"),
+        })
+    }
+}
+
+impl TryFrom<i32> for BossGarageShuffle {
+    type Error = ();
+
+    fn try_from(v: i32) -> Result<Self, Self::Error> {
+        match v {
+            x if x == BossGarageShuffle::Vanilla as i32 => Ok(BossGarageShuffle::Vanilla),
+            x if x == BossGarageShuffle::HubBosses as i32 => Ok(BossGarageShuffle::HubBosses),
+            x if x == BossGarageShuffle::IncludeOxide as i32 => Ok(BossGarageShuffle::IncludeOxide),
+            _ => Err(()),
+        }
+    }
+}
+
+/// How the four tracks of every gem cup are chosen
+#[derive(Debug, Clone, Copy)]
+pub enum GemCupTracks {
+    Vanilla = 0,
+    /// Any four tracks per cup, tracks can show up in several cups
+    Random = 1,
+    /// Every track once, before any track is used a second time
+    NoRepeats = 2,
+    /// Every colored cup gets the tracks of one hub
+    ByHub = 3,
+}
+
+impl std::fmt::Display for GemCupTracks {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            GemCupTracks::Vanilla => String::from("Vanilla"),
+            GemCupTracks::Random => String::from("Random"),
+            GemCupTracks::NoRepeats => String::from("NoRepeats"),
+            GemCupTracks::ByHub => String::from("ByHub"),
+        })
+    }
+}
+
+impl TryFrom<i32> for GemCupTracks {
+    type Error = ();
+
+    fn try_from(v: i32) -> Result<Self, Self::Error> {
+        match v {
+            x if x == GemCupTracks::Vanilla as i32 => Ok(GemCupTracks::Vanilla),
+            x if x == GemCupTracks::Random as i32 => Ok(GemCupTracks::Random),
+            x if x == GemCupTracks::NoRepeats as i32 => Ok(GemCupTracks::NoRepeats),
+            x if x == GemCupTracks::ByHub as i32 => Ok(GemCupTracks::ByHub),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Requirement for turning Oxide's Challenge into Oxide's Final Challenge:
+/// `count` items of type `item_type`
+#[derive(Debug, Clone, Copy)]
+pub struct FinalOxideUnlock {
+    pub item_type: RequiredItem,
+    pub count: u8,
+}
+
+/// Item types selectable for Oxide's Final Challenge, in the order the UI
+/// lists them
+pub const FINAL_OXIDE_UNLOCK_ITEMS: [RequiredItem; 9] = [
+    RequiredItem::SapphireRelic,
+    RequiredItem::GoldRelic,
+    RequiredItem::PlatinumRelic,
+    RequiredItem::GoldOrPlatinumRelic,
+    RequiredItem::AnyRelic,
+    RequiredItem::Trophy,
+    RequiredItem::AnyCtrToken,
+    RequiredItem::AnyGem,
+    RequiredItem::Key,
+];
+
+impl FinalOxideUnlock {
+    pub fn get_requirement(&self) -> UnlockRequirementItem {
+        UnlockRequirementItem {
+            item_type: self.item_type,
+            count: self.count,
+        }
+    }
+
+    /// The value base mods before the flexible final challenge read:
+    /// 1 for 18 gold or platinum relics, 0 for anything else, which they
+    /// treat as 18 sapphire relics
+    pub fn get_legacy_id(&self) -> u16 {
+        u16::from(self.item_type == RequiredItem::GoldOrPlatinumRelic && self.count == 18)
+    }
+}
+
+impl Default for FinalOxideUnlock {
+    fn default() -> Self {
+        FinalOxideUnlock {
+            item_type: RequiredItem::SapphireRelic,
+            count: 18,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -242,27 +956,692 @@ impl TryFrom<i32> for RelicTime {
     }
 }
 
-impl TryFrom<i32> for FinalOxideUnlock {
+impl TryFrom<(i32, i32)> for FinalOxideUnlock {
     type Error = ();
 
-    fn try_from(v: i32) -> Result<Self, Self::Error> {
-        match v {
-            x if x == FinalOxideUnlock::SappireRelics18 as i32 => {
-                Ok(FinalOxideUnlock::SappireRelics18)
+    /// Build from the UI's (item type index, count) pair
+    fn try_from(v: (i32, i32)) -> Result<Self, Self::Error> {
+        let (item_index, count) = v;
+        let item_type = *FINAL_OXIDE_UNLOCK_ITEMS
+            .get(usize::try_from(item_index).map_err(|_| ())?)
+            .ok_or(())?;
+        let count = u8::try_from(count).map_err(|_| ())?;
+
+        Ok(FinalOxideUnlock { item_type, count })
+    }
+}
+
+impl std::fmt::Display for FinalOxideUnlock {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} (x{})", self.item_type, self.count)
+    }
+}
+
+/// What the player has to achieve to beat the seed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Goal {
+    BeatOxide,
+    CollectAllGems,
+    PlatinumRelics(u8),
+    BeatHubBosses,
+    TrophyHunt(u8),
+}
+
+impl Goal {
+    pub fn get_id(&self) -> u8 {
+        match self {
+            Goal::BeatOxide => 0,
+            Goal::CollectAllGems => 1,
+            Goal::PlatinumRelics(_) => 2,
+            Goal::BeatHubBosses => 3,
+            Goal::TrophyHunt(_) => 4,
+        }
+    }
+
+    pub fn get_count(&self) -> u8 {
+        match self {
+            Goal::PlatinumRelics(x) | Goal::TrophyHunt(x) => *x,
+            _ => 0,
+        }
+    }
+}
+
+impl Goal {
+    /// Items the player needs to collect for this goal, if any
+    pub fn get_required_items(&self) -> Option<UnlockRequirementItem> {
+        match self {
+            Goal::CollectAllGems => Some(UnlockRequirementItem { item_type: RequiredItem::AnyGem, count: 5 }),
+            Goal::PlatinumRelics(x) => Some(UnlockRequirementItem { item_type: RequiredItem::PlatinumRelic, count: *x }),
+            Goal::TrophyHunt(x) => Some(UnlockRequirementItem { item_type: RequiredItem::Trophy, count: *x }),
+            Goal::BeatOxide | Goal::BeatHubBosses => None,
+        }
+    }
+}
+
+impl TryFrom<(i32, i32)> for Goal {
+    type Error = ();
+
+    /// Build from the UI's (goal index, count) pair
+    fn try_from(v: (i32, i32)) -> Result<Self, Self::Error> {
+        let (goal_index, count) = v;
+        let count = u8::try_from(count).map_err(|_| ())?;
+
+        match goal_index {
+            0 => Ok(Goal::BeatOxide),
+            1 => Ok(Goal::CollectAllGems),
+            2 => Ok(Goal::PlatinumRelics(count)),
+            3 => Ok(Goal::BeatHubBosses),
+            4 => Ok(Goal::TrophyHunt(count)),
+            _ => Err(()),
+        }
+    }
+}
+
+impl std::fmt::Display for Goal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            Goal::BeatOxide => String::from("Beat Oxide's Final Challenge"),
+            Goal::CollectAllGems => String::from("Collect all 5 Gems"),
+            Goal::PlatinumRelics(x) => format!("Collect {x} Platinum Relics"),
+            Goal::BeatHubBosses => String::from("Beat all 4 Hub Bosses"),
+            Goal::TrophyHunt(x) => format!("Trophy Hunt: Collect {x} of 16 Trophies"),
+        })
+    }
+}
+
+/// Items the player already owns when starting a new save file
+#[derive(Debug, Clone, Default)]
+pub struct StartingInventory {
+    pub items: BTreeMap<RaceReward, u8>,
+}
+
+impl StartingInventory {
+    /// Get every single starting item, e.g. 2 keys are returned as 2 entries
+    pub fn get_items(&self) -> Vec<RaceReward> {
+        let mut all_items = Vec::new();
+
+        for (item, count) in &self.items {
+            for _ in 0..*count {
+                all_items.push(*item);
+            }
+        }
+
+        all_items
+    }
+}
+
+impl TryFrom<&str> for StartingInventory {
+    type Error = String;
+
+    /// Parse a comma separated list of items with an optional count each,
+    /// like "2 Keys, 1 Red CTR Token, 4 Trophies"
+    fn try_from(v: &str) -> Result<Self, Self::Error> {
+        let mut items = BTreeMap::new();
+
+        for entry in v.split(',').map(str::trim).filter(|x| !x.is_empty()) {
+            let (count, item_name) = match entry.split_once(' ') {
+                Some((count, item_name)) if count.parse::<u8>().is_ok() => {
+                    (count.parse::<u8>().expect("checked by match guard"), item_name)
+                }
+                _ => (1, entry),
+            };
+            let item = item_name
+                .parse::<RaceReward>()
+                .map_err(|()| format!("Unknown starting item '{item_name}'!"))?;
+            if matches!(item, RaceReward::BeatTheGame) || item.is_filler() {
+                return Err(format!("Cannot start with '{item_name}'!"));
+            }
+
+            let item_count: &mut u8 = items.entry(item).or_default();
+            *item_count = item_count.saturating_add(count);
+        }
+
+        Ok(StartingInventory { items })
+    }
+}
+
+impl std::fmt::Display for StartingInventory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.items.is_empty() {
+            return write!(f, "-");
+        }
+
+        write!(f, "{}", self.items
+            .iter()
+            .map(|(item, count)| format!("{count} {item}"))
+            .collect::<Vec<_>>()
+            .join(", "))
+    }
+}
+
+/// A single entry of the location exclusion list
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LocationExclusion {
+    Location(ItemLocation),
+    RaceType(RaceType),
+    Level(LevelID),
+}
+
+impl LocationExclusion {
+    pub fn contains(&self, location: &ItemLocation) -> bool {
+        match self {
+            LocationExclusion::Location(x) => x == location,
+            LocationExclusion::RaceType(x) => *x == location.racetype,
+            LocationExclusion::Level(x) => *x == location.levelid,
+        }
+    }
+}
+
+impl std::fmt::Display for LocationExclusion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LocationExclusion::Location(x) => write!(f, "{} {}", x.levelid, x.racetype),
+            LocationExclusion::RaceType(x) => write!(f, "{x}"),
+            LocationExclusion::Level(x) => write!(f, "{x}"),
+        }
+    }
+}
+
+/// Locations that only ever hold items the logic does not depend on
+#[derive(Debug, Clone, Default)]
+pub struct ExcludedLocations {
+    pub exclusions: Vec<LocationExclusion>,
+}
+
+impl ExcludedLocations {
+    pub fn contains(&self, location: &ItemLocation) -> bool {
+        self.exclusions.iter().any(|x| x.contains(location))
+    }
+}
+
+impl TryFrom<&str> for ExcludedLocations {
+    type Error = String;
+
+    /// Parse a comma separated list of levels ("CrashCove"), race types
+    /// ("RelicRacePlatinum") or single locations ("CrashCove TrophyRace")
+    fn try_from(v: &str) -> Result<Self, Self::Error> {
+        let mut exclusions = Vec::new();
+
+        for entry in v.split(',').map(str::trim).filter(|x| !x.is_empty()) {
+            let exclusion = match entry.split_once(' ') {
+                Some((level, racetype)) => {
+                    match (level.parse::<LevelID>(), racetype.parse::<RaceType>()) {
+                        (Ok(levelid), Ok(racetype)) => {
+                            Some(LocationExclusion::Location(ItemLocation { levelid, racetype }))
+                        }
+                        _ => None,
+                    }
+                }
+                None => entry
+                    .parse::<LevelID>()
+                    .map(LocationExclusion::Level)
+                    .or_else(|()| entry.parse::<RaceType>().map(LocationExclusion::RaceType))
+                    .ok(),
+            };
+
+            match exclusion {
+                Some(x) => exclusions.push(x),
+                None => return Err(format!("Unknown location to exclude '{entry}'!")),
             }
-            x if x == FinalOxideUnlock::GoldAndPlatinumRelics18 as i32 => {
-                Ok(FinalOxideUnlock::GoldAndPlatinumRelics18)
+        }
+
+        Ok(ExcludedLocations { exclusions })
+    }
+}
+
+impl std::fmt::Display for ExcludedLocations {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.exclusions.is_empty() {
+            return write!(f, "-");
+        }
+
+        write!(f, "{}", self.exclusions
+            .iter()
+            .map(std::string::ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(", "))
+    }
+}
+
+/// Items placed on specific locations by hand, with everything else still
+/// being shuffled
+#[derive(Debug, Clone, Default)]
+pub struct ItemPlando {
+    pub placements: BTreeMap<ItemLocation, RaceReward>,
+}
+
+impl TryFrom<&str> for ItemPlando {
+    type Error = String;
+
+    /// Parse a comma separated list of locations with their items, like
+    /// "RoosTubes BossRace: Key, RedGemCup GemCup: Red Gem"
+    fn try_from(v: &str) -> Result<Self, Self::Error> {
+        let mut placements = BTreeMap::new();
+
+        for entry in v.split(',').map(str::trim).filter(|x| !x.is_empty()) {
+            let Some((location, item_name)) = entry.split_once(':') else {
+                return Err(format!("Missing ':' between location and item in '{entry}'!"));
+            };
+            let location = match location.trim().split_once(' ') {
+                Some((level, racetype)) => match (level.parse::<LevelID>(), racetype.parse::<RaceType>()) {
+                    (Ok(levelid), Ok(racetype)) => ItemLocation { levelid, racetype },
+                    _ => return Err(format!("Unknown location '{}'!", location.trim())),
+                },
+                None => return Err(format!("Unknown location '{}'!", location.trim())),
+            };
+            let item = item_name
+                .trim()
+                .parse::<RaceReward>()
+                .map_err(|()| format!("Unknown item '{}'!", item_name.trim()))?;
+
+            if placements.insert(location, item).is_some() {
+                return Err(format!(
+                    "'{} {}' has more than one item placed on it!",
+                    location.levelid,
+                    location.racetype,
+                ));
             }
+        }
+
+        Ok(ItemPlando { placements })
+    }
+}
+
+impl std::fmt::Display for ItemPlando {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.placements.is_empty() {
+            return write!(f, "-");
+        }
+
+        write!(f, "{}", self.placements
+            .iter()
+            .map(|(location, item)| format!("{} {}: {}", location.levelid, location.racetype, item))
+            .collect::<Vec<_>>()
+            .join(", "))
+    }
+}
+
+pub const MIN_RELIC_TIME_PERCENT: u16 = 25;
+/// Any higher and Tiny Arena's sapphire time no longer fits the ROM database
+pub const MAX_RELIC_TIME_PERCENT: u16 = 290;
+
+#[derive(Debug, Clone, Default)]
+pub struct RelicTimeSettings {
+    pub targets: RelicTimeTargets,
+    pub overrides: RelicTimeOverrides,
+}
+
+/// How to derive every track's relic times from its vanilla relic times
+#[derive(Debug, Clone, Copy, Default)]
+pub enum RelicTimeTargets {
+    #[default]
+    Vanilla,
+    /// Percentage of the vanilla times
+    Scaled(u16),
+    /// Random percentage of the vanilla times within (min, max), per track
+    Randomized(u16, u16),
+}
+
+impl TryFrom<(i32, i32, i32)> for RelicTimeTargets {
+    type Error = ();
+
+    /// Build from the UI's (mode index, percent, max percent) triple
+    fn try_from(v: (i32, i32, i32)) -> Result<Self, Self::Error> {
+        let (mode, percent, max_percent) = v;
+        let percent = u16::try_from(percent).map_err(|_| ())?;
+        let max_percent = u16::try_from(max_percent).map_err(|_| ())?;
+
+        match mode {
+            0 => Ok(RelicTimeTargets::Vanilla),
+            1 => Ok(RelicTimeTargets::Scaled(percent)),
+            2 => Ok(RelicTimeTargets::Randomized(percent, max_percent)),
             _ => Err(()),
         }
     }
 }
 
-impl std::fmt::Display for FinalOxideUnlock {
+impl std::fmt::Display for RelicTimeTargets {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", match self {
-            FinalOxideUnlock::SappireRelics18 => String::from("SappireRelics18"),
-            FinalOxideUnlock::GoldAndPlatinumRelics18 => String::from("GoldAndPlatinumRelics18"),
+        match self {
+            RelicTimeTargets::Vanilla => write!(f, "Vanilla"),
+            RelicTimeTargets::Scaled(x) => write!(f, "Scaled ({x}%)"),
+            RelicTimeTargets::Randomized(x, y) => write!(f, "Randomized ({x}% - {y}%)"),
+        }
+    }
+}
+
+/// Relic times set by hand for specific tracks
+#[derive(Debug, Clone, Default)]
+pub struct RelicTimeOverrides {
+    pub times: BTreeMap<LevelID, RelicTimes>,
+}
+
+impl TryFrom<&str> for RelicTimeOverrides {
+    type Error = String;
+
+    /// Parse a comma separated list of tracks with their sapphire, gold and
+    /// platinum times, like "CrashCove 1:10:00/1:00:00/0:50:00"
+    fn try_from(v: &str) -> Result<Self, Self::Error> {
+        let mut times = BTreeMap::new();
+
+        for entry in v.split(',').map(str::trim).filter(|x| !x.is_empty()) {
+            let Some((level, relic_times)) = entry.split_once(' ') else {
+                return Err(format!("Missing relic times in '{entry}'!"));
+            };
+            let levelid = match level.parse::<LevelID>() {
+                Ok(x) if get_relic_track_data(x).is_some() => x,
+                _ => return Err(format!("'{level}' has no relic race!")),
+            };
+            let parsed_times: Vec<u32> = relic_times
+                .split('/')
+                .map(parse_relic_time)
+                .collect::<Option<Vec<u32>>>()
+                .unwrap_or_default();
+            let [sapphire, gold, platinum] = parsed_times[..] else {
+                return Err(format!(
+                    "Relic times in '{entry}' need to look like\n\
+                        '1:17:00/1:05:00/0:52:00' (Sapphire/Gold/Platinum)!"
+                ));
+            };
+
+            times.insert(levelid, RelicTimes { sapphire, gold, platinum });
+        }
+
+        Ok(RelicTimeOverrides { times })
+    }
+}
+
+impl std::fmt::Display for RelicTimeOverrides {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.times.is_empty() {
+            return write!(f, "-");
+        }
+
+        write!(f, "{}", self.times
+            .iter()
+            .map(|(levelid, times)| format!("{levelid} {times}"))
+            .collect::<Vec<_>>()
+            .join(", "))
+    }
+}
+
+/// The base mod has room for this many hints
+pub const MAX_HINTS: u8 = 20;
+
+/// Number of hints to generate, per kind of hint
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HintSettings {
+    /// "The 3rd Key is in Glacier Park"
+    pub item_area_hints: u8,
+    /// "DingoCanyon RelicRacePlatinum is not required"
+    pub not_required_hints: u8,
+    /// "The Red Gem is on a BossRace"
+    pub race_type_hints: u8,
+}
+
+impl HintSettings {
+    pub fn get_total(&self) -> u8 {
+        self.item_area_hints
+            .saturating_add(self.not_required_hints)
+            .saturating_add(self.race_type_hints)
+    }
+}
+
+impl TryFrom<(i32, i32, i32)> for HintSettings {
+    type Error = ();
+
+    /// Build from the UI's (item area, not required, race type) hint counts
+    fn try_from(v: (i32, i32, i32)) -> Result<Self, Self::Error> {
+        let (item_area_hints, not_required_hints, race_type_hints) = v;
+
+        Ok(HintSettings {
+            item_area_hints: u8::try_from(item_area_hints).map_err(|_| ())?,
+            not_required_hints: u8::try_from(not_required_hints).map_err(|_| ())?,
+            race_type_hints: u8::try_from(race_type_hints).map_err(|_| ())?,
         })
     }
 }
+
+impl std::fmt::Display for HintSettings {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} item area, {} not required, {} race type",
+            self.item_area_hints,
+            self.not_required_hints,
+            self.race_type_hints,
+        )
+    }
+}
+
+/// Hints for a printable sheet handed to racers, independent of the hints
+/// written to the ROM
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HintSheetSettings {
+    /// "CrashCove TrophyRace is on the way of the hero"
+    pub way_of_the_hero_hints: u8,
+    /// "Nothing at TigerTemple is required"
+    pub barren_hints: u8,
+    /// "RoosTubes BossRace has a Key"
+    pub item_location_hints: u8,
+    /// Hints revealed right at the start, the others get revealed one at a
+    /// time afterwards
+    pub starting_hints: u8,
+    /// Minutes between revealing hints, 0 for revealing all at the start
+    pub reveal_interval_minutes: u16,
+}
+
+impl HintSheetSettings {
+    pub fn get_total(&self) -> u8 {
+        self.way_of_the_hero_hints
+            .saturating_add(self.barren_hints)
+            .saturating_add(self.item_location_hints)
+    }
+}
+
+impl TryFrom<(i32, i32, i32, i32, i32)> for HintSheetSettings {
+    type Error = ();
+
+    /// Build from the UI's (way of the hero, barren, item location, starting
+    /// hints, reveal interval) values
+    fn try_from(v: (i32, i32, i32, i32, i32)) -> Result<Self, Self::Error> {
+        let (way_of_the_hero_hints, barren_hints, item_location_hints, starting_hints, reveal_interval_minutes) = v;
+
+        let to_count = |x: i32| u8::try_from(x).ok().filter(|x| *x <= 99).ok_or(());
+        Ok(HintSheetSettings {
+            way_of_the_hero_hints: to_count(way_of_the_hero_hints)?,
+            barren_hints: to_count(barren_hints)?,
+            item_location_hints: to_count(item_location_hints)?,
+            starting_hints: to_count(starting_hints)?,
+            reveal_interval_minutes: u16::try_from(reveal_interval_minutes)
+                .ok()
+                .filter(|x| *x <= 999)
+                .ok_or(())?,
+        })
+    }
+}
+
+impl std::fmt::Display for HintSheetSettings {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} way of the hero, {} barren, {} item location, {} at the start, then one every {} minutes",
+            self.way_of_the_hero_hints,
+            self.barren_hints,
+            self.item_location_hints,
+            self.starting_hints,
+            self.reveal_interval_minutes,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The UI defaults with shuffled race rewards
+    const DEFAULT_SETTINGS: &str = "rnd1;000;-;0;1;0;0;0;0;0;0;\ngen00018000;\ninv-;\nexc-;\nrlt0;-;\nhnt000000;\n\
+        hsh00000000;000;\npln-;\nqol00000;\ntrk001;\nwrt100;";
+
+    /// Parse the default settings with the given lines swapped in
+    fn get_settings(lines: &[&str]) -> Result<SeedSettings, String> {
+        let settings_string = get_settings_string(lines);
+        SeedSettings::try_from(settings_string.as_str())
+    }
+
+    fn get_settings_string(lines: &[&str]) -> String {
+        DEFAULT_SETTINGS
+            .lines()
+            .map(|line| lines.iter().find(|x| x[..3] == line[..3]).copied().unwrap_or(line))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Writing out parsed settings has to give back the very same line
+    fn assert_round_trip(line: &str) -> SeedSettings {
+        let settings = get_settings(&[line]).unwrap();
+        assert_eq!(settings.to_string(), get_settings_string(&[line]));
+        settings
+    }
+
+    #[test]
+    fn final_challenge_settings() {
+        let settings = assert_round_trip("gen00516000;");
+        assert_eq!(settings.general.oxide_final_challenge_unlock.item_type, RequiredItem::Trophy);
+        assert_eq!(settings.general.oxide_final_challenge_unlock.count, 16);
+        assert!(settings.validate().is_ok());
+
+        // More trophies than there are
+        assert!(get_settings(&["gen00517000;"]).unwrap().validate().is_err());
+        // Unshuffled platinum relics are never expected of the player
+        assert!(get_settings(&["gen00201000;"]).unwrap().validate().is_err());
+        assert!(get_settings(&["rnd1;001;-;0;1;0;0;0;0;0;0;", "gen00201000;"]).unwrap().validate().is_ok());
+    }
+
+    #[test]
+    fn goal_settings() {
+        let settings = assert_round_trip("gen00018412;");
+        assert!(matches!(settings.general.goal, Goal::TrophyHunt(12)));
+        assert!(settings.validate().is_ok());
+
+        assert!(get_settings(&["gen00018417;"]).unwrap().validate().is_err());
+        assert!(get_settings(&["gen00018400;"]).unwrap().validate().is_err());
+        // Platinum relics need to be shuffled to be collected
+        assert!(get_settings(&["gen00018205;"]).unwrap().validate().is_err());
+        assert!(get_settings(&["rnd1;001;-;0;1;0;0;0;0;0;0;", "gen00018205;"]).unwrap().validate().is_ok());
+        assert!(get_settings(&["gen00018500;"]).is_err());
+    }
+
+    #[test]
+    fn starting_inventory_settings() {
+        let settings = assert_round_trip("inv4 Trophy, 1 Red CTR Token;");
+        assert_eq!(settings.general.starting_inventory.get_items().len(), 5);
+        assert!(settings.validate().is_ok());
+
+        let settings = get_settings(&["inv1 red ctr token, 4 Trophies;"]).unwrap();
+        assert_eq!(settings.general.starting_inventory.to_string(), "4 Trophy, 1 Red CTR Token");
+
+        assert!(get_settings(&["inv2 Bananas;"]).is_err());
+        assert!(get_settings(&["invNothing;"]).is_err());
+        // Keys are not shuffled by default
+        assert!(get_settings(&["inv1 Key;"]).unwrap().validate().is_err());
+        assert!(get_settings(&["rnd1;100;-;0;1;0;0;0;0;0;0;", "inv5 Keys;"]).unwrap().validate().is_err());
+    }
+
+    #[test]
+    fn excluded_location_settings() {
+        let settings = assert_round_trip("excCrashCove, RelicRacePlatinum, TigerTemple TrophyRace;");
+        let excluded_locations = &settings.general.excluded_locations;
+        assert!(excluded_locations.contains(&ItemLocation { levelid: LevelID::CrashCove, racetype: RaceType::BossRace }));
+        assert!(excluded_locations.contains(&ItemLocation { levelid: LevelID::CocoPark, racetype: RaceType::RelicRacePlatinum }));
+        assert!(!excluded_locations.contains(&ItemLocation { levelid: LevelID::TigerTemple, racetype: RaceType::RelicRaceGold }));
+        assert!(settings.validate().is_ok());
+
+        assert!(get_settings(&["excCrashCove Foo;"]).is_err());
+        // Roo's Tubes holds a key, as keys are not shuffled by default
+        assert!(get_settings(&["excRoosTubes BossRace;"]).unwrap().validate().is_err());
+        assert!(get_settings(&["excOxideStation;"]).unwrap().validate().is_err());
+    }
+
+    #[test]
+    fn item_plando_settings() {
+        let settings = assert_round_trip("plnCrashCove TrophyRace: Trophy, RoosTubes TrophyRace: Red CTR Token;");
+        assert_eq!(settings.general.item_plando.placements.len(), 2);
+        assert!(settings.validate().is_ok());
+
+        assert!(get_settings(&["plnCrashCove TrophyRace Trophy;"]).is_err());
+        assert!(get_settings(&["plnCrashCove TrophyRace: Trophy, CrashCove TrophyRace: Key;"]).is_err());
+        // Roo's Tubes keeps its key, as keys are not shuffled by default
+        assert!(get_settings(&["plnRoosTubes BossRace: Trophy;"]).unwrap().validate().is_err());
+        assert!(get_settings(&["plnCrashCove TrophyRace: Key;"]).unwrap().validate().is_err());
+        assert!(get_settings(&["rnd1;-;-;0;1;0;0;0;0;0;0;", "plnCrashCove TrophyRace: Trophy;"])
+            .unwrap()
+            .validate()
+            .is_err());
+    }
+
+    #[test]
+    fn relic_time_settings() {
+        let settings = assert_round_trip("rlt2080120;CrashCove 1:10:00/1:00:00/0:50:00, TurboTrack 2:00:00/1:30:00/1:00:00;");
+        assert!(matches!(settings.general.relic_times.targets, RelicTimeTargets::Randomized(80, 120)));
+        assert_eq!(
+            settings.general.relic_times.overrides.times[&LevelID::CrashCove],
+            RelicTimes { sapphire: 7000, gold: 6000, platinum: 5000 },
+        );
+        assert!(settings.validate().is_ok());
+
+        assert!(get_settings(&["rlt1020;-;"]).unwrap().validate().is_err());
+        assert!(get_settings(&["rlt2120080;-;"]).unwrap().validate().is_err());
+        assert!(get_settings(&["rlt0;CrashCove 1:00:00/1:10:00/0:50:00;"]).unwrap().validate().is_err());
+        assert!(get_settings(&["rlt0;CrashCove 1:10:00;"]).is_err());
+        assert!(get_settings(&["rlt0;SkullRock 1:10:00/1:00:00/0:50:00;"]).is_err());
+    }
+
+    #[test]
+    fn hint_settings() {
+        let settings = assert_round_trip("hnt050312;");
+        assert_eq!(settings.general.hints.item_area_hints, 5);
+        assert_eq!(settings.general.hints.not_required_hints, 3);
+        assert_eq!(settings.general.hints.race_type_hints, 12);
+        assert!(settings.validate().is_ok());
+
+        assert!(get_settings(&["hnt100902;"]).unwrap().validate().is_err());
+        assert!(get_settings(&["hnt0503;"]).is_err());
+    }
+
+    #[test]
+    fn hint_sheet_settings() {
+        let settings = assert_round_trip("hsh03020405;010;");
+        assert_eq!(settings.general.hint_sheet.get_total(), 9);
+        assert_eq!(settings.general.hint_sheet.starting_hints, 5);
+        assert_eq!(settings.general.hint_sheet.reveal_interval_minutes, 10);
+        assert!(settings.validate().is_ok());
+
+        assert!(get_settings(&["hsh0302;010;"]).is_err());
+        assert!(get_settings(&["hsh03020405;"]).is_err());
+        assert!(get_settings(&["hsh03020405;1000;"]).is_err());
+    }
+
+    #[test]
+    fn trick_settings() {
+        let settings = assert_round_trip("trk102;");
+        assert!(settings.tricks.helper_tiziano);
+        assert!(!settings.tricks.helper_ta);
+        assert_eq!(settings.tricks.logic_level, LogicLevel::Expert);
+        assert_eq!(assert_round_trip("trk010;").tricks.logic_level, LogicLevel::Beginner);
+
+        assert!(get_settings(&["trk003;"]).is_err());
+        assert!(get_settings(&["trk01;"]).is_err());
+    }
+
+    #[test]
+    fn write_settings() {
+        let settings = assert_round_trip("wrt011;");
+        assert!(!settings.write_spoilerlog);
+        assert!(settings.write_patchfile);
+        assert!(settings.race_mode);
+        assert!(!assert_round_trip("wrt100;").race_mode);
+
+        assert!(get_settings(&["wrt10;"]).is_err());
+    }
+}
@@ -1,26 +1,129 @@
-use std::{io, path::PathBuf};
+use std::{io, path::{Path, PathBuf}};
 
 use serde_json::{json, to_string_pretty};
 
 use crate::seed_generation::seed_settings::{RewardShuffle, WarppadShuffle};
 use crate::seed_generation::{
+    difficulty_curve::get_difficulty_curve,
+    html_spoilerlog::write_html_spoilerlog,
     game_world::{
         BattleArenaRewards, BossGarage, BossRaceRewards, GemCupRewards, RelicRaceOnlyRewards,
         Rewards, TokensAndRelicRewards, TrophyRaceRewards, WarpPad,
     },
-    randomization_datastructures::{GameSetup, UnlockRequirement, UnlockRequirementItem},
+    randomization_datastructures::{GameSetup, UnlockRequirement, UnlockRequirementItem, UNLOCKABLES},
+    relic_times::format_relic_time,
+    seed_estimate::SeedEstimate,
+    rom_patching::base_patch::BasePatch,
     seed_settings::SeedSettings,
+    version::get_version_info,
 };
 
 pub fn write_spoilerlog(
-    new_rom_path: &PathBuf,
+    new_rom_path: &Path,
     game_setup: GameSetup,
     seed: u32,
     chosen_settings: &SeedSettings,
+    seed_estimate: &SeedEstimate,
+    base_patch: &BasePatch,
 ) -> Result<(), io::Error> {
+    let spoilerlog = get_spoilerlog(game_setup, seed, chosen_settings, seed_estimate, base_patch);
+
+    std::fs::write(get_spoilerlog_path(new_rom_path, "json"), to_string_pretty(&spoilerlog).unwrap())?;
+    write_html_spoilerlog(&get_spoilerlog_path(new_rom_path, "html"), &spoilerlog)
+}
+
+/// Path of the spoiler log written next to the new ROM
+pub fn get_spoilerlog_path(new_rom_path: &Path, extension: &str) -> PathBuf {
+    let mut spoilerlog_path = new_rom_path.to_path_buf();
+    let file_stem = spoilerlog_path.clone();
+    let file_stem = file_stem.file_stem().unwrap();
+    spoilerlog_path.pop();
+    spoilerlog_path.push(format!(
+        "{}{}{}",
+        file_stem.to_str().unwrap(),
+        "_spoilers.",
+        extension
+    ));
+
+    spoilerlog_path
+}
+
+pub fn get_spoilerlog(
+    game_setup: GameSetup,
+    seed: u32,
+    chosen_settings: &SeedSettings,
+    seed_estimate: &SeedEstimate,
+    base_patch: &BasePatch,
+) -> serde_json::Value {
     let game_world = game_setup.game_world;
-    let spoilerlog = json!({
+    let difficulty_curve = get_difficulty_curve(&game_world, chosen_settings);
+    let race_rewards = game_world.get_race_rewards();
+    json!({
         "seed_hash": get_seed_hash(seed),
+        "version": get_version_info(base_patch),
+        "goal": chosen_settings.general.goal.to_string(),
+        "starting_inventory": game_setup.starting_inventory
+            .iter()
+            .map(|(item, count)| format!("{item} (x{count})"))
+            .collect::<Vec<_>>(),
+        "relic_times": game_setup.relic_times
+            .iter()
+            .map(|(level_id, times)| (level_id.to_string(), json!({
+                "sapphire": format_relic_time(times.sapphire),
+                "gold": format_relic_time(times.gold),
+                "platinum": format_relic_time(times.platinum),
+            })))
+            .collect::<serde_json::Map<String, serde_json::Value>>(),
+        "unlockables": UNLOCKABLES
+            .iter()
+            .map(|unlockable| (
+                unlockable.to_string(),
+                json!(race_rewards
+                    .iter()
+                    .find(|(_, reward)| *reward == unlockable)
+                    .map(|(x, _)| format!("{} {}", x.levelid, x.racetype))
+                    .or_else(|| game_setup.bonus_unlockables
+                        .iter()
+                        .find(|(_, bonus)| *bonus == unlockable)
+                        .map(|(x, _)| format!("{} {} (bonus)", x.levelid, x.racetype)))
+                    .unwrap_or(String::from("Vanilla unlock"))),
+            ))
+            .collect::<serde_json::Map<String, serde_json::Value>>(),
+        "gem_cup_tracks": game_setup.gem_cup_tracks
+            .iter()
+            .map(|(cup, tracks)| (
+                cup.to_string(),
+                json!(tracks.iter().map(std::string::ToString::to_string).collect::<Vec<_>>()),
+            ))
+            .collect::<serde_json::Map<String, serde_json::Value>>(),
+        "hub_entrances": game_world.get_hub_entrances()
+            .iter()
+            .map(|(door, hub)| (format!("{door} door"), json!(hub.to_string())))
+            .collect::<serde_json::Map<String, serde_json::Value>>(),
+        "hints": game_setup.hints
+            .iter()
+            .map(std::string::ToString::to_string)
+            .collect::<Vec<_>>(),
+        "seed_estimate": {
+            "required_races": seed_estimate.required_locations
+                .iter()
+                .map(|x| format!("{} {}", x.levelid, x.racetype))
+                .collect::<Vec<_>>(),
+            "races": seed_estimate.num_races,
+            "relic_races": seed_estimate.num_relic_races,
+            "gem_cups": seed_estimate.num_gem_cups,
+            "boss_races": seed_estimate.num_boss_races,
+            "estimated_duration": seed_estimate.to_string(),
+        },
+        "difficulty_curve": difficulty_curve
+            .iter()
+            .map(|x| json!({
+                "race": format!("{} {}", x.location.levelid, x.location.racetype),
+                "sphere": x.sphere,
+                "trophies": x.num_trophies,
+                "bot_difficulty": x.bot_difficulty,
+            }))
+            .collect::<Vec<_>>(),
         "adventure": {
             "hub_1": {
                 "warppad_1_crashcove": get_formatted_warppad(game_world.hub_1.warppad_1),
@@ -73,11 +176,24 @@ pub fn write_spoilerlog(
                 "warppad_unlock_requirements": chosen_settings.randomization.warppad_unlock_requirements.to_string(),
                 "bossgarage_unlock_requirements": chosen_settings.randomization.bossgarage_unlock_requirements.to_string(),
                 "autounlock_ctrchallenge_relicrace": chosen_settings.randomization.autounlock_ctrchallenge_relicrace.to_string(),
+                "gentle_difficulty_curve": chosen_settings.randomization.gentle_difficulty_curve.to_string(),
+                "shuffle_unlockables": chosen_settings.randomization.shuffle_unlockables.to_string(),
+                "gem_cup_tracks": chosen_settings.randomization.gem_cup_tracks.to_string(),
+                "boss_garage_shuffle": chosen_settings.randomization.boss_garage_shuffle.to_string(),
+                "shuffle_hub_entrances": chosen_settings.randomization.shuffle_hub_entrances.to_string(),
             },
             "general": {
                 "relicrace_required_minimum_time": chosen_settings.general.rr_required_minimum_time.to_string(),
                 "relicrace_require_perfects": chosen_settings.general.rr_require_perfects.to_string(),
                 "oxide_final_challenge_unlock": chosen_settings.general.oxide_final_challenge_unlock.to_string(),
+                "goal": chosen_settings.general.goal.to_string(),
+                "starting_inventory": chosen_settings.general.starting_inventory.to_string(),
+                "excluded_locations": chosen_settings.general.excluded_locations.to_string(),
+                "item_plando": chosen_settings.general.item_plando.to_string(),
+                "relic_time_targets": chosen_settings.general.relic_times.targets.to_string(),
+                "relic_time_overrides": chosen_settings.general.relic_times.overrides.to_string(),
+                "hints": chosen_settings.general.hints.to_string(),
+                "hint_sheet": chosen_settings.general.hint_sheet.to_string(),
             },
             "qol": {
                 "skip_mask_hints": chosen_settings.qol.skip_mask_hints.to_string(),
@@ -87,54 +203,33 @@ pub fn write_spoilerlog(
             "tricks": {
                 "helper_tiziano": chosen_settings.tricks.helper_tiziano.to_string(),
                 "helper_ta": chosen_settings.tricks.helper_ta.to_string(),
+                "logic_level": chosen_settings.tricks.logic_level.to_string(),
+                "tricks_in_logic": chosen_settings.tricks.get_enabled_tricks()
+                    .iter()
+                    .map(std::string::ToString::to_string)
+                    .collect::<Vec<_>>(),
             }
         }
-    });
+    })
+}
 
-    let mut spoilerlog_path = new_rom_path.clone();
-    let file_stem = spoilerlog_path.clone();
-    let file_stem = file_stem.file_stem().unwrap();
-    spoilerlog_path.pop();
-    spoilerlog_path.push(format!(
-        "{}{}",
-        file_stem.to_str().unwrap(),
-        "_spoilers.json"
-    ));
+/// Characters of the seed hash, numbered like the hash icons the base mod
+/// shows on the title screen
+pub const SEED_HASH_CHARACTERS: [&str; 16] = [
+    "Crash", "Cortex", "Tiny", "Coco", "NGin", "Dingodile", "Polar", "Pura",
+    "Pinstripe", "PapuPapu", "RipperRoo", "KomodoJoe", "N.Tropy", "Penta", "FakeCrash", "N.Oxide",
+];
 
-    std::fs::write(&spoilerlog_path, to_string_pretty(&spoilerlog).unwrap())
+/// The four characters of the seed hash as indices into SEED_HASH_CHARACTERS,
+/// one per byte of the seed
+pub fn get_seed_hash_icons(seed: u32) -> [usize; 4] {
+    seed.to_be_bytes().map(|x| usize::from(x % 16))
 }
 
 pub fn get_seed_hash(seed: u32) -> String {
-    fn get_character(seed_short: u8) -> String {
-        let character_id: u8 = seed_short % 16;
-        match character_id {
-            0 => "Crash",
-            1 => "Cortex",
-            2 => "Tiny",
-            3 => "Coco",
-            4 => "NGin",
-            5 => "Dingodile",
-            6 => "Polar",
-            7 => "Pura",
-            8 => "Pinstripe",
-            9 => "PapuPapu",
-            10 => "RipperRoo",
-            11 => "KomodoJoe",
-            12 => "N.Tropy",
-            13 => "Penta",
-            14 => "FakeCrash",
-            15 => "N.Oxide",
-            _ => panic!(),
-        }
-        .to_string()
-    }
-
-    let character_icon_1 = get_character(((seed >> 24) & 0xFF) as u8);
-    let character_icon_2 = get_character(((seed >> 16) & 0xFF) as u8);
-    let character_icon_3 = get_character(((seed >> 8) & 0xFF) as u8);
-    let character_icon_4 = get_character((seed & 0xFF) as u8);
-
-    format!("{character_icon_1}, {character_icon_2}, {character_icon_3}, {character_icon_4}")
+    get_seed_hash_icons(seed)
+        .map(|x| SEED_HASH_CHARACTERS[x])
+        .join(", ")
 }
 
 fn get_formatted_warppad(warppad: WarpPad) -> serde_json::Value {
@@ -171,6 +266,7 @@ fn get_formatted_bossgarage(bossgarage: BossGarage) -> serde_json::Value {
     match bossgarage.requirement {
         UnlockRequirement::Item(UnlockRequirementItem { item_type, count }) => {
             json!({
+                "boss": bossgarage.boss.to_string(),
                 "unlock": format!("{} (x{})", item_type, count),
                 "reward": get_formatted_reward(bossgarage.reward)
             })
@@ -182,10 +278,18 @@ fn get_formatted_bossgarage(bossgarage: BossGarage) -> serde_json::Value {
                 .collect::<Vec<_>>()
                 .join(", ");
             json!({
+                "boss": bossgarage.boss.to_string(),
                 "unlock": level_list,
                 "reward": get_formatted_reward(bossgarage.reward)
             })
         }
+        UnlockRequirement::Trick(x) => {
+            json!({
+                "boss": bossgarage.boss.to_string(),
+                "unlock": x.to_string(),
+                "reward": get_formatted_reward(bossgarage.reward)
+            })
+        }
     }
 }
 
@@ -0,0 +1,187 @@
+use std::collections::HashMap;
+
+use crate::seed_generation::{
+    game_world::{get_vanilla_gameworld, GameWorld, BOSS_CHARACTERS},
+    item_randomization::{
+        player_inventory::PlayerInventory,
+        randomize_items::get_final_item_placement,
+    },
+    randomization_datastructures::{
+        ItemLocation, LevelID, RaceReward, RaceType, RequiredItem, UnlockRequirement, UnlockRequirementItem,
+        UnlockStage,
+    },
+    seed_settings::SeedSettings,
+};
+
+/// Items the player can click into the tracker, the ones progression
+/// depends on
+pub const TRACKED_ITEMS: [RaceReward; 15] = [
+    RaceReward::Trophy,
+    RaceReward::RedCtrToken,
+    RaceReward::GreenCtrToken,
+    RaceReward::BlueCtrToken,
+    RaceReward::YellowCtrToken,
+    RaceReward::PurpleCtrToken,
+    RaceReward::SapphireRelic,
+    RaceReward::GoldRelic,
+    RaceReward::PlatinumRelic,
+    RaceReward::Key,
+    RaceReward::RedGem,
+    RaceReward::GreenGem,
+    RaceReward::BlueGem,
+    RaceReward::YellowGem,
+    RaceReward::PurpleGem,
+];
+
+/// One warp pad or boss garage shown in the tracker
+#[derive(Debug)]
+pub struct TrackerEntry {
+    pub name: String,
+    /// Track that can be marked as beaten, None for boss garages
+    pub track: Option<LevelID>,
+    pub requirement_1: String,
+    /// Requirement of the relic races and CTR challenge, if the warp pad has
+    /// a second unlock
+    pub requirement_2: Option<String>,
+    pub unlocked_1: bool,
+    pub unlocked_2: bool,
+}
+
+/// Warp pad locations in the order the tracker lists them
+pub fn get_warppad_locations() -> Vec<LevelID> {
+    let mut locations: Vec<LevelID> = get_vanilla_gameworld().get_warppad_links().into_keys().collect();
+    locations.sort();
+    locations
+}
+
+/// Warp pads and boss garages of a generated seed, with whether the player
+/// can enter them with the given inventory
+pub fn get_tracker_entries(
+    inventory: &PlayerInventory,
+    game_world: &GameWorld,
+    chosen_settings: &SeedSettings,
+) -> Vec<TrackerEntry> {
+    let item_placement = get_final_item_placement(game_world, chosen_settings);
+    let passes = |location: &ItemLocation| -> bool {
+        item_placement
+            .get(location)
+            .is_some_and(|(requirements, _)| inventory.does_pass_requirements(requirements))
+    };
+
+    let warppad_links = game_world.get_warppad_links();
+    let warppad_unlocks = game_world.get_warppad_unlocks();
+    let mut entries = Vec::new();
+
+    for location in get_warppad_locations() {
+        let track = warppad_links[&location];
+        let requirement_2 = warppad_unlocks.get(&(track, UnlockStage::Two));
+
+        entries.push(TrackerEntry {
+            name: if location == track {
+                track.to_string()
+            } else {
+                format!("{location} warp pad: {track}")
+            },
+            track: Some(track),
+            requirement_1: get_formatted_requirement(warppad_unlocks[&(track, UnlockStage::One)]),
+            requirement_2: requirement_2.map(|x| get_formatted_requirement(*x)),
+            unlocked_1: item_placement
+                .iter()
+                .any(|(x, (requirements, _))| {
+                    x.levelid == track
+                        && x.racetype != RaceType::BossRace
+                        && inventory.does_pass_requirements(requirements)
+                }),
+            unlocked_2: requirement_2.is_some()
+                && passes(&ItemLocation{levelid: track, racetype: RaceType::RelicRaceSapphire}),
+        });
+    }
+
+    let garage_unlocks = game_world.get_garage_unlocks();
+    let boss_hubs = game_world.get_boss_hubs();
+    for boss in BOSS_CHARACTERS {
+        entries.push(TrackerEntry {
+            name: format!("{} Boss Garage: {boss}", boss_hubs[&boss]),
+            track: None,
+            requirement_1: match &garage_unlocks[&boss] {
+                UnlockRequirement::Item(x) => get_formatted_requirement(Some(*x)),
+                UnlockRequirement::LevelList(x) => x
+                    .iter()
+                    .map(std::string::ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                UnlockRequirement::Trick(x) => x.to_string(),
+            },
+            requirement_2: None,
+            unlocked_1: passes(&ItemLocation{levelid: boss.get_levelid(), racetype: RaceType::BossRace}),
+            unlocked_2: false,
+        });
+    }
+
+    entries
+}
+
+/// Warp pads without knowing the seed, with the requirements the player typed
+/// in by warp pad location. Requirements that cannot be read count as not met.
+pub fn get_spoiler_free_entries(
+    inventory: &PlayerInventory,
+    requirements: &HashMap<LevelID, (String, String)>,
+) -> Vec<TrackerEntry> {
+    let vanilla_unlocks = get_vanilla_gameworld().get_warppad_unlocks();
+    let is_met = |text: &str| -> bool {
+        parse_requirement(text)
+            .is_some_and(|x| inventory.does_pass_requirements(&vec![UnlockRequirement::Item(x)]))
+    };
+
+    get_warppad_locations()
+        .into_iter()
+        .map(|location| {
+            let (requirement_1, requirement_2) = requirements.get(&location).cloned().unwrap_or_default();
+            let has_stage_2 = vanilla_unlocks.contains_key(&(location, UnlockStage::Two));
+
+            TrackerEntry {
+                name: location.to_string(),
+                track: Some(location),
+                unlocked_1: is_met(&requirement_1),
+                unlocked_2: has_stage_2 && is_met(&requirement_2),
+                requirement_1,
+                requirement_2: has_stage_2.then_some(requirement_2),
+            }
+        })
+        .collect()
+}
+
+/// Read a requirement as written in the spoiler log or the starting
+/// inventory, like "Trophy (x3)", "3 Trophies" or "Open"
+pub fn parse_requirement(text: &str) -> Option<UnlockRequirementItem> {
+    let text = text.trim();
+    if text.eq_ignore_ascii_case("open") {
+        return Some(UnlockRequirementItem { item_type: RequiredItem::Trophy, count: 0 });
+    }
+
+    let (count, item_name) = if let Some((item_name, count)) = text
+        .strip_suffix(')')
+        .and_then(|x| x.rsplit_once("(x"))
+    {
+        (count.trim().parse::<u8>().ok()?, item_name)
+    } else {
+        match text.split_once(' ') {
+            Some((count, item_name)) if count.parse::<u8>().is_ok() => {
+                (count.parse::<u8>().expect("checked by match guard"), item_name)
+            }
+            _ => (1, text),
+        }
+    };
+
+    Some(UnlockRequirementItem {
+        item_type: item_name.parse().ok()?,
+        count,
+    })
+}
+
+fn get_formatted_requirement(requirement: Option<UnlockRequirementItem>) -> String {
+    match requirement {
+        Some(x) if x.count > 0 => format!("{} (x{})", x.item_type, x.count),
+        _ => "Open".to_string(),
+    }
+}
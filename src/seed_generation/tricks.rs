@@ -0,0 +1,62 @@
+use crate::seed_generation::{
+    randomization_datastructures::{ItemLocation, LevelID, RaceType},
+    seed_settings::{LogicLevel, TrickSettings},
+};
+
+/// A shortcut or skip that some relic times can only be beaten with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Trick {
+    /// Papu's Pyramid shortcut
+    Tiziano,
+    /// Tiny Arena super-shortcut
+    TaSuperShortcut,
+    PolarPassSkip,
+    HotAirSkywaySkip,
+}
+
+impl Trick {
+    /// The lowest logic level that expects the trick of the player. The
+    /// item box helpers make their tricks one level easier.
+    pub fn get_logic_level(&self, trick_settings: &TrickSettings) -> LogicLevel {
+        match self {
+            Trick::Tiziano if trick_settings.helper_tiziano => LogicLevel::Standard,
+            Trick::TaSuperShortcut if trick_settings.helper_ta => LogicLevel::Standard,
+            Trick::Tiziano | Trick::TaSuperShortcut | Trick::PolarPassSkip => LogicLevel::Expert,
+            Trick::HotAirSkywaySkip => LogicLevel::Standard,
+        }
+    }
+}
+
+impl std::fmt::Display for Trick {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            Trick::Tiziano => "Tiziano",
+            Trick::TaSuperShortcut => "TA Super-Shortcut",
+            Trick::PolarPassSkip => "Polar Pass Skip",
+            Trick::HotAirSkywaySkip => "Hot Air Skyway Skip",
+        })
+    }
+}
+
+pub const TRICKS: [Trick; 4] = [
+    Trick::Tiziano,
+    Trick::TaSuperShortcut,
+    Trick::PolarPassSkip,
+    Trick::HotAirSkywaySkip,
+];
+
+/// Relic races that need a trick, by the track the race is on
+pub const TRICK_REQUIREMENTS: [(LevelID, RaceType, Trick); 5] = [
+    (LevelID::PapusPyramid, RaceType::RelicRacePlatinum, Trick::Tiziano),
+    (LevelID::TinyArena, RaceType::RelicRaceGold, Trick::TaSuperShortcut),
+    (LevelID::TinyArena, RaceType::RelicRacePlatinum, Trick::TaSuperShortcut),
+    (LevelID::PolarPass, RaceType::RelicRacePlatinum, Trick::PolarPassSkip),
+    (LevelID::HotAirSkyway, RaceType::RelicRacePlatinum, Trick::HotAirSkywaySkip),
+];
+
+pub fn get_required_trick(location: &ItemLocation) -> Option<Trick> {
+    TRICK_REQUIREMENTS
+        .iter()
+        .find(|(levelid, racetype, _)| *levelid == location.levelid && *racetype == location.racetype)
+        .map(|(_, _, trick)| *trick)
+}
@@ -0,0 +1,72 @@
+use serde_json::json;
+
+use crate::seed_generation::rom_patching::base_patch::BasePatch;
+
+/// Format version of the ROM database and the progress data the base mod
+/// keeps behind it, both described in docs/RandoDatabase.md. Raise it
+/// whenever the base mod reads or writes either of them differently.
+pub const DB_VERSION: u16 = 1;
+
+/// Generator version as shown to the user, e.g. "beta 2" for 1.0.0-beta.2
+pub fn get_generator_version() -> String {
+    match env!("CARGO_PKG_VERSION_PRE") {
+        "" => env!("CARGO_PKG_VERSION").to_owned(),
+        pre => pre.replace('.', " "),
+    }
+}
+
+/// Versions recorded in patch files and spoiler logs
+pub fn get_version_info(base_patch: &BasePatch) -> serde_json::Value {
+    json!({
+        "generator_version": get_generator_version(),
+        "db_version": DB_VERSION,
+        "base_patch_md5": base_patch.hash,
+    })
+}
+
+/// Check the version of a database read from a memory card or RAM, as the
+/// randomizer data behind it is only laid out as expected if it matches
+pub fn check_db_version(db_version: Option<u16>) -> Result<(), String> {
+    if db_version == Some(DB_VERSION) {
+        Ok(())
+    } else {
+        Err(format!(
+            "The randomizer data was written by a different generator version,\n\
+                which is not compatible with this generator ({}).",
+            get_generator_version(),
+        ))
+    }
+}
+
+/// Compare the versions recorded in a patch file or spoiler log to this
+/// generator. Different database versions cannot be mixed, while a different
+/// generator version or base patch only earns a warning.
+pub fn check_version_info(version_info: &serde_json::Value, file_type: &str) -> Result<Option<String>, String> {
+    let Some(generator_version) = version_info["generator_version"].as_str() else {
+        return Ok(Some(format!(
+            "This {file_type} has no version info, it was likely made by an older generator\n\
+                and may not match this generator ({}).",
+            get_generator_version(),
+        )));
+    };
+
+    if version_info["db_version"].as_u64() != Some(u64::from(DB_VERSION)) {
+        return Err(format!(
+            "This {file_type} was made by generator version {generator_version},\n\
+                which is not compatible with this generator ({}).",
+            get_generator_version(),
+        ));
+    }
+    if generator_version != get_generator_version() {
+        return Ok(Some(format!(
+            "This {file_type} was made by generator version {generator_version},\n\
+                this is version {}.",
+            get_generator_version(),
+        )));
+    }
+    if version_info["base_patch_md5"].as_str() != Some(BasePatch::builtin().hash.as_str()) {
+        return Ok(Some(format!("This {file_type} was made with a different base patch.")));
+    }
+
+    Ok(None)
+}
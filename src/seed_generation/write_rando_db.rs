@@ -1,18 +1,45 @@
-use std::{collections::HashMap, io::{Seek, Write}, path::PathBuf};
+use std::{collections::{BTreeMap, HashMap}, io::{Read, Seek, Write}, path::Path};
 
-use crate::seed_generation::randomization_datastructures::{GameSetup, RequiredItem, SettingValue, UnlockStage};
+use crate::seed_generation::{
+    game_world::{get_vanilla_gameworld, Hubs},
+    hints::Hint,
+    randomization_datastructures::{
+        GameSetup, RaceType, RequiredItem, SettingID, SettingValue, UnlockRequirementItem, UnlockStage, UNLOCKABLES,
+    },
+    relic_times::get_vanilla_relic_times,
+    rom_patching::base_patch::BaseManifest,
+    version::DB_VERSION,
+};
 
-pub fn write_db_to_rom<'a>(rom_filepath: &PathBuf, randomized_game: &GameSetup) -> Result<(), &'a str> {
-    // Transform the randomized game into bytes to write
-    let write_location = 0xF220;
+/// Marker in front of the database, for clients to find it in RAM
+pub const DB_START_MARKER: [u8; 6] = [0xDB, 0xDA, 0x00, 0x0D, 0xDB, 0xDA];
+/// Marker behind the last database entry, followed by the end-of-list marker
+pub const DB_END_MARKER: [u8; 8] = [0xDB, 0xDA, 0xAA, 0x0D, 0xDB, 0xDA, 0xFF, 0xFF];
+/// Key of the database version, which sorts in front of all other entries
+const DB_VERSION_KEY: u32 = 0x9F00 << 16;
+const DB_PREFIX_SETTINGS: u32 = 0xAF00;
 
+/// Write the database to the ROM at the offset the base patch's manifest
+/// gives, as long as it fits into the space the manifest reserves for it
+pub fn write_db_to_rom<'a>(rom_filepath: &Path, randomized_game: &GameSetup, manifest: &BaseManifest) -> Result<(), &'a str> {
+    // Transform the randomized game into bytes to write
     let database = get_database_vec(randomized_game);
+    if database.len() as u64 > manifest.db_max_size {
+        return Err("The randomization is larger than the space the base patch reserves for it!");
+    }
+    let write_location = manifest.db_offset;
 
     // Write bytes
     let filehandle = std::fs::File::options().write(true).open(rom_filepath);
 
     match filehandle {
         Ok(mut x) => {
+            // Never grow the ROM if the manifest's offset is off
+            let rom_size = x.metadata().map_or(0, |metadata| metadata.len());
+            if write_location + database.len() as u64 > rom_size {
+                return Err("The randomization does not fit into the patched ROM at the database offset!");
+            }
+
             let seek_result = x.seek(std::io::SeekFrom::Start(write_location));
             if seek_result.is_ok() {
                 let write_result = x.write(&database);
@@ -30,12 +57,16 @@ pub fn write_db_to_rom<'a>(rom_filepath: &PathBuf, randomized_game: &GameSetup)
 
 fn get_database_vec(randomized_game: &GameSetup) -> Vec<u8> {
     // To reference what the resulting vec is supposed to look like, see
-    // mod repository, src/CTRRandomizer_database.c file
+    // mod repository, src/CTRRandomizer_database.c file, and
+    // docs/RandoDatabase.md for the tables and their encodings
 
     // Turn randomizer game into database hashmap
     let mut key_value_db: HashMap<u32, u16> = HashMap::new();
     let mut db_as_vec: Vec<u8> = Vec::new();
 
+    // Version header
+    key_value_db.insert(DB_VERSION_KEY, DB_VERSION);
+
     // Warppad Links
     let db_prefix_levelids: u32 = 0xA000;
     for (key, value) in randomized_game.game_world.get_warppad_links() {
@@ -64,33 +95,141 @@ fn get_database_vec(randomized_game: &GameSetup) -> Vec<u8> {
         key_value_db.insert(
             (cur_db_prefix | level_id as u32) << 16,
             match requirement {
-                Some(req) => {req.item_type as u16 | ((u16::from(req.count) & 0x1F) << 11)},
+                Some(req) => {get_encoded_requirement(&req)},
                 None => {RequiredItem::Trophy as u16} // implicit -> count: 0
             }
         );
     }
 
+    // Starting Inventory, one entry per item with the item in the lower half
+    // of the key, like the fields of hints
+    let db_prefix_starting_inventory: u32 = 0xA400;
+    for (index, (item, count)) in randomized_game.starting_inventory.iter().enumerate() {
+        key_value_db.insert(
+            ((db_prefix_starting_inventory | index as u32) << 16) | *item as u32,
+            u16::from(*count),
+        );
+    }
+
+    // Relic Times, in centiseconds
+    // Only written for tracks that don't use their vanilla times
+    let db_prefix_relic_times: u32 = 0xA500;
+    let vanilla_relic_times = get_vanilla_relic_times();
+    for (level_id, times) in &randomized_game.relic_times {
+        if vanilla_relic_times.get(level_id) == Some(times) {
+            continue;
+        }
+        for (racetype, time) in [
+            (RaceType::RelicRaceSapphire, times.sapphire),
+            (RaceType::RelicRaceGold, times.gold),
+            (RaceType::RelicRacePlatinum, times.platinum),
+        ] {
+            key_value_db.insert(
+                ((db_prefix_relic_times | *level_id as u32) << 16) | racetype as u32,
+                u16::try_from(time).unwrap_or(u16::MAX),
+            );
+        }
+    }
+
+    // Hints, one entry per field of every hint
+    let db_prefix_hints: u32 = 0xA600;
+    for (hint_index, hint) in randomized_game.hints.iter().enumerate() {
+        let hint_fields: Vec<u16> = match hint {
+            Hint::ItemInHub { item, ordinal, hub } => vec![
+                hint.get_id(),
+                *item as u16,
+                u16::from(ordinal.unwrap_or(0)),
+                get_encoded_hub(hub),
+            ],
+            Hint::LocationNotRequired(location) => vec![
+                hint.get_id(),
+                location.levelid as u16,
+                location.racetype as u16,
+            ],
+            Hint::ItemOnRaceType { item, racetype } => vec![
+                hint.get_id(),
+                *item as u16,
+                *racetype as u16,
+            ],
+        };
+        for (field, value) in hint_fields.into_iter().enumerate() {
+            key_value_db.insert(((db_prefix_hints | hint_index as u32) << 16) | field as u32, value);
+        }
+    }
+
+    // Shuffled Unlockables
+    // Listed unlockables stay locked until their race reward or bonus unlock
+    // is collected, all others keep their vanilla unlock
+    let db_prefix_unlockables: u32 = 0xA700;
+    for reward in randomized_game.game_world.get_race_rewards().into_values() {
+        if UNLOCKABLES.contains(&reward) {
+            key_value_db.insert((db_prefix_unlockables << 16) | reward as u32, 1);
+        }
+    }
+    for unlockable in randomized_game.bonus_unlockables.values() {
+        key_value_db.insert((db_prefix_unlockables << 16) | *unlockable as u32, 1);
+    }
+
+    // Bonus Unlockables, handed out on top of the race reward
+    let db_prefix_bonus_unlockables: u32 = 0xAB00;
+    for (location, unlockable) in &randomized_game.bonus_unlockables {
+        key_value_db.insert(
+            ((db_prefix_bonus_unlockables | location.levelid as u32) << 16) | location.racetype as u32,
+            *unlockable as u16,
+        );
+    }
+
+    // Gem Cup Tracks, one entry per track of every cup
+    // Only written if the line-ups aren't vanilla
+    let db_prefix_gem_cup_tracks: u32 = 0xA800;
+    for (cup, tracks) in &randomized_game.gem_cup_tracks {
+        for (index, track) in tracks.iter().enumerate() {
+            key_value_db.insert(
+                ((db_prefix_gem_cup_tracks | *cup as u32) << 16) | index as u32,
+                *track as u16,
+            );
+        }
+    }
+
+    // Boss Garages, the boss behind the garage of every hub
+    // Only written for garages that don't hold their vanilla boss
+    let db_prefix_boss_garages: u32 = 0xA900;
+    let vanilla_boss_hubs = get_vanilla_gameworld().get_boss_hubs();
+    for (boss, hub) in randomized_game.game_world.get_boss_hubs() {
+        if vanilla_boss_hubs.get(&boss) == Some(&hub) {
+            continue;
+        }
+        key_value_db.insert((db_prefix_boss_garages << 16) | u32::from(get_encoded_hub(&hub)), boss as u16);
+    }
+
+    // Hub Entrances, the hub behind the door of every hub
+    // Only written for doors that don't lead to their vanilla hub
+    let db_prefix_hub_entrances: u32 = 0xAA00;
+    for (door, hub) in randomized_game.game_world.get_hub_entrances() {
+        if door == hub {
+            continue;
+        }
+        key_value_db.insert((db_prefix_hub_entrances << 16) | u32::from(get_encoded_hub(&door)), get_encoded_hub(&hub));
+    }
+
     // Settings
-    let db_prefix_settings: u32 = 0xAF00;
     for (setting_id, value) in &randomized_game.settings {
         let db_value = match value {
             SettingValue::Boolean(x) => u16::from(*x),
             SettingValue::RelicDifficulty(x) => *x as u16,
             SettingValue::BossGarageRequirements(x) => *x as u16,
-            SettingValue::OxideRequiredRelics(x) => *x as u16,
+            SettingValue::RequirementItem(x) => get_encoded_requirement(x),
+            SettingValue::OxideRequiredRelics(x) => x.get_legacy_id(),
             SettingValue::SeedHashPart(x) => *x,
+            // Goal ID in the lower byte, goal count in the upper byte
+            SettingValue::Goal(x) => u16::from(x.get_id()) | (u16::from(x.get_count()) << 8),
         };
 
-        key_value_db.insert((db_prefix_settings | *setting_id as u32) << 16, db_value);
+        key_value_db.insert((DB_PREFIX_SETTINGS | *setting_id as u32) << 16, db_value);
     }
 
     // Begin u8 vec with RAM marker for client
-    db_as_vec.push(0xDB);
-    db_as_vec.push(0xDA);
-    db_as_vec.push(0x00);
-    db_as_vec.push(0x0D);
-    db_as_vec.push(0xDB);
-    db_as_vec.push(0xDA);
+    db_as_vec.extend_from_slice(&DB_START_MARKER);
 
     // Use ordered hashmap keys to get an ordered vec later
     let mut db_keys: Vec<&u32> = key_value_db.keys().collect();
@@ -111,14 +250,125 @@ fn get_database_vec(randomized_game: &GameSetup) -> Vec<u8> {
     }
 
     // Finish u8 vec with RAM marker for client and end-of-list marker
-    db_as_vec.push(0xDB);
-    db_as_vec.push(0xDA);
-    db_as_vec.push(0xAA);
-    db_as_vec.push(0x0D);
-    db_as_vec.push(0xDB);
-    db_as_vec.push(0xDA);
-    db_as_vec.push(0xFF);
-    db_as_vec.push(0xFF);
+    db_as_vec.extend_from_slice(&DB_END_MARKER);
 
     db_as_vec
 }
+
+/// Read back a database starting at the first byte, like one found in RAM.
+/// Returns the entries and the number of bytes the database takes up,
+/// including its markers.
+pub fn read_database(bytes: &[u8]) -> Option<(BTreeMap<u32, u16>, usize)> {
+    if !bytes.starts_with(&DB_START_MARKER) {
+        return None;
+    }
+
+    let mut database = BTreeMap::new();
+    let mut offset = DB_START_MARKER.len();
+    loop {
+        if bytes.get(offset..offset + DB_END_MARKER.len())? == DB_END_MARKER {
+            return Some((database, offset + DB_END_MARKER.len()));
+        }
+
+        // Undo the byte order of get_database_vec
+        let entry = bytes.get(offset..offset + 6)?;
+        let key = u32::from_le_bytes([entry[2], entry[3], entry[0], entry[1]]);
+        let value = u16::from_le_bytes([entry[4], entry[5]]);
+        database.insert(key, value);
+        offset += 6;
+    }
+}
+
+/// Read the database back from a ROM written by write_db_to_rom
+pub fn read_db_from_rom(rom_filepath: &Path, manifest: &BaseManifest) -> Result<BTreeMap<u32, u16>, String> {
+    let mut bytes = Vec::new();
+    std::fs::File::open(rom_filepath)
+        .and_then(|mut x| {
+            x.seek(std::io::SeekFrom::Start(manifest.db_offset))?;
+            x.take(manifest.db_max_size).read_to_end(&mut bytes)
+        })
+        .map_err(|_| "Could not read the ROM!".to_owned())?;
+
+    read_database(&bytes)
+        .map(|(database, _)| database)
+        .ok_or_else(|| "The ROM holds no randomizer data!\nIs it a randomized ROM?".to_owned())
+}
+
+/// Seed of a database read back with read_database, None if the seed hash
+/// is not shown
+pub fn get_db_seed(database: &BTreeMap<u32, u16>) -> Option<u32> {
+    let get_setting = |x: SettingID| -> u32 {
+        u32::from(database.get(&((DB_PREFIX_SETTINGS | x as u32) << 16)).copied().unwrap_or_default())
+    };
+
+    let seed = (get_setting(SettingID::SeedHash1) << 16) | get_setting(SettingID::SeedHash2);
+    (seed != 0).then_some(seed)
+}
+
+/// Version of a database read back with read_database, None if it was
+/// written before databases had a version
+pub fn get_db_version(database: &BTreeMap<u32, u16>) -> Option<u16> {
+    database.get(&DB_VERSION_KEY).copied()
+}
+
+fn get_encoded_hub(hub: &Hubs) -> u16 {
+    match hub {
+        Hubs::NSanityBeach => 0,
+        Hubs::TheLostRuins => 1,
+        Hubs::GlacierPark => 2,
+        Hubs::CitadelCity => 3,
+        Hubs::GemStoneValley => 4,
+    }
+}
+
+/// Requirements are stored as the item type in the lower 11 bits, and the
+/// required count in the upper 5 bits
+fn get_encoded_requirement(requirement: &UnlockRequirementItem) -> u16 {
+    requirement.item_type as u16 | ((u16::from(requirement.count) & 0x1F) << 11)
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use super::*;
+    use crate::seed_generation::{randomize_game::get_randomized_game, seed_settings::SeedSettings};
+
+    fn get_database(gen: &str) -> BTreeMap<u32, u16> {
+        let settings = SeedSettings::try_from(
+            format!(
+                "rnd1;111;11;1;1;0;0;0;0;0;0;\n{gen}\ninv-;\nexc-;\nrlt0;-;\nhnt000000;\nhsh00000000;000;\npln-;\n\
+                qol10000;\ntrk002;\nwrt000;"
+            )
+            .as_str(),
+        )
+        .unwrap();
+        settings.validate().unwrap();
+        let game = get_randomized_game(ChaCha8Rng::seed_from_u64(1), 1, &settings).unwrap();
+        read_database(&get_database_vec(&game)).unwrap().0
+    }
+
+    fn get_setting(database: &BTreeMap<u32, u16>, setting_id: SettingID) -> u16 {
+        database[&((DB_PREFIX_SETTINGS | setting_id as u32) << 16)]
+    }
+
+    #[test]
+    fn final_challenge_keeps_legacy_setting() {
+        // 18 gold or platinum relics
+        let database = get_database("gen00318000;");
+        assert_eq!(get_setting(&database, SettingID::OxideRequiredRelics), 1);
+        assert_eq!(
+            get_setting(&database, SettingID::OxideFinalChallengeRequirement),
+            RequiredItem::GoldOrPlatinumRelic as u16 | (18 << 11),
+        );
+
+        // 16 trophies, which older base mods cannot express
+        let database = get_database("gen00516000;");
+        assert_eq!(get_setting(&database, SettingID::OxideRequiredRelics), 0);
+        assert_eq!(
+            get_setting(&database, SettingID::OxideFinalChallengeRequirement),
+            RequiredItem::Trophy as u16 | (16 << 11),
+        );
+    }
+}